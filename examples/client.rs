@@ -2,7 +2,7 @@ use std::process::ExitCode;
 
 #[tokio::main]
 async fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
-    let connection = zbus::Connection::system().await?;
+    let connection = bluez_zbus::system_connection().await?;
 
     println!("finding adapters");
     let adapters = bluez_zbus::get_adapters(&connection).await?;
@@ -10,12 +10,8 @@ async fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
 
     //Start discovery
     println!("starting discovery");
-    futures_util::future::join_all(
-        adapters
-            .iter()
-            .map(|(_path, adapter)| adapter.start_discovery()),
-    )
-    .await;
+    futures_util::future::join_all(adapters.values().map(|adapter| adapter.start_discovery()))
+        .await;
     println!("discovery completed");
 
     //Get devices
@@ -26,11 +22,7 @@ async fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
         }
         Ok(devices) => {
             for (_path, proxy) in devices {
-                let name = proxy
-                    .device
-                    .name()
-                    .await
-                    .unwrap_or(proxy.device.address().await.unwrap());
+                let name = proxy.display_name().await?;
 
                 let icon = proxy
                     .device
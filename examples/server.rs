@@ -36,33 +36,26 @@ async fn main() -> anyhow::Result<()> {
 
     tracing_subscriber::registry().with(log_filter).init();
 
-    let system_conn = zbus::Connection::system().await?;
+    let system_conn = bluez_zbus::system_connection().await?;
 
     let (agent, mut receiver) = bluez_zbus::agent1::create();
 
     let agent_path = ObjectPath::from_static_str_unchecked(AGENT_PATH);
 
-    tracing::debug!("connecting agent");
-
-    system_conn.object_server().at(&agent_path, agent).await?;
-
     tracing::debug!("connecting to bluez agent manager");
 
     let bluez = bluez_zbus::agent_manager1::AgentManager1Proxy::new(&system_conn).await?;
 
     tracing::debug!("registering agent");
 
-    bluez
-        .register_agent(
-            &agent_path,
-            <&'static str>::from(bluez_zbus::agent1::Capability::DisplayYesNo),
-        )
-        .await?;
-
-    if let Err(why) = bluez.request_default_agent(&agent_path).await {
-        _ = bluez.unregister_agent(&agent_path).await;
-        Err(why)?;
-    }
+    let registration = bluez_zbus::agent1::register(
+        &system_conn,
+        &bluez,
+        agent,
+        agent_path,
+        bluez_zbus::agent1::Capability::DisplayYesNo,
+    )
+    .await?;
 
     tracing::debug!("registered");
 
@@ -70,40 +63,74 @@ async fn main() -> anyhow::Result<()> {
         tracing::debug!(?msg, "message received");
 
         match msg {
-            bluez_zbus::agent1::Message::RequestAuthorization { device, response } => {
-                _ = response.send(true);
+            bluez_zbus::agent1::Message::RequestAuthorization {
+                device,
+                alias,
+                response,
+            } => {
+                response.accept();
             }
             bluez_zbus::agent1::Message::RequestConfirmation {
                 device,
+                alias,
                 passkey,
                 response,
             } => {
                 let (agent_tx, agent_rx) = oneshot::channel();
-                let connection = zbus::Connection::system().await?;
-                let device = bluez_zbus::get_device(&connection, device).await.unwrap();
-                let _ = launch_ui(Some(agent_tx), device.device.name().await.unwrap(), passkey.to_string());
-                let res = agent_rx.await.unwrap();
-                _ = response.send(res);
+                let device_name = alias.unwrap_or_else(|| device.to_string());
+                let _ = launch_ui(Some(agent_tx), device_name, passkey.to_string());
+                if agent_rx.await.unwrap() {
+                    response.accept();
+                } else {
+                    response.reject();
+                }
+            }
+            bluez_zbus::agent1::Message::RequestPasskey {
+                device,
+                alias,
+                response,
+            } => {
+                response.reject();
             }
-            bluez_zbus::agent1::Message::RequestPasskey { device, response } => {
-                _ = response.send(None);
+            bluez_zbus::agent1::Message::RequestPinCode {
+                device,
+                alias,
+                response,
+            } => {
+                response.reject();
             }
-            bluez_zbus::agent1::Message::RequestPinCode { device, response } => {
-                _ = response.send(None);
+            bluez_zbus::agent1::Message::AuthorizeService {
+                device,
+                uuid,
+                response,
+            } => {
+                response.accept();
             }
-            bluez_zbus::agent1::Message::AuthorizeService { device, uuid } => {}
             bluez_zbus::agent1::Message::Cancel => {}
             bluez_zbus::agent1::Message::DisplayPasskey {
                 device,
                 passkey,
                 entered,
-            } => {}
-            bluez_zbus::agent1::Message::DisplayPinCode { device, pincode } => {}
+            } => {
+                let passkey = bluez_zbus::agent1::PasskeyDisplay::new(passkey, entered);
+                tracing::info!(?device, %passkey, "type the passkey on the device");
+            }
+            bluez_zbus::agent1::Message::DisplayPinCode {
+                device,
+                pincode,
+                dismissal,
+            } => {
+                tracing::info!(?device, pincode, "type the PIN code on the keyboard");
+                tokio::spawn(async move {
+                    dismissal.dismissed().await;
+                    tracing::info!("PIN code no longer needed");
+                });
+            }
             bluez_zbus::agent1::Message::Release => {}
         }
     }
 
-    _ = bluez.unregister_agent(&agent_path).await;
+    _ = registration.unregister().await;
 
     tracing::debug!("exiting");
 
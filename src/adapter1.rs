@@ -25,7 +25,7 @@ trait Adapter1 {
 	fn connect_device(
 		&self,
 		properties: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
-	) -> zbus::Result<()>;
+	) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
 
 	/// GetDiscoveryFilters method
 	fn get_discovery_filters(&self) -> zbus::Result<Vec<String>>;
@@ -117,3 +117,27 @@ trait Adapter1 {
 	#[zbus(property, name = "UUIDs")]
 	fn uuids(&self) -> zbus::Result<Vec<String>>;
 }
+
+/// A role the controller supports, as listed in `Roles`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+	Central,
+	Peripheral,
+	/// Both roles at the same time.
+	CentralPeripheral,
+}
+
+impl TryFrom<&str> for Role {
+	type Error = zbus::zvariant::Error;
+
+	fn try_from(role: &str) -> Result<Self, Self::Error> {
+		match role {
+			"central" => Ok(Role::Central),
+			"peripheral" => Ok(Role::Peripheral),
+			"central-peripheral" => Ok(Role::CentralPeripheral),
+			_ => Err(zbus::zvariant::Error::Message(format!(
+				"unknown adapter role: {role}"
+			))),
+		}
+	}
+}
@@ -0,0 +1,33 @@
+//! Bluetooth device address types.
+
+/// Type of a Bluetooth device address, as reported in `AddressType`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AddressType {
+	/// A public address assigned by the manufacturer, also used by BR/EDR devices.
+	Public,
+	/// A random address, which privacy-enabled LE devices may rotate.
+	Random,
+}
+
+impl From<AddressType> for &'static str {
+	fn from(address_type: AddressType) -> &'static str {
+		match address_type {
+			AddressType::Public => "public",
+			AddressType::Random => "random",
+		}
+	}
+}
+
+impl TryFrom<&str> for AddressType {
+	type Error = zbus::zvariant::Error;
+
+	fn try_from(address_type: &str) -> Result<Self, Self::Error> {
+		match address_type {
+			"public" => Ok(AddressType::Public),
+			"random" => Ok(AddressType::Random),
+			_ => Err(zbus::zvariant::Error::Message(format!(
+				"unknown address type: {address_type}"
+			))),
+		}
+	}
+}
@@ -0,0 +1,189 @@
+//! Integrations for creating bluez advertisement monitors.
+//!
+//! BlueZ discovers monitors through an `org.freedesktop.DBus.ObjectManager`, so
+//! the monitor objects must be served below a path that also serves
+//! [`zbus::fdo::ObjectManager`], and that path is what gets passed to
+//! `org.bluez.AdvertisementMonitorManager1.RegisterMonitor`.
+
+use tokio::sync::mpsc;
+use zbus::zvariant::OwnedObjectPath;
+
+pub fn create(params: Params) -> (Monitor, mpsc::Receiver<Message>) {
+    let (message_sender, message_receiver) = tokio::sync::mpsc::channel(1);
+
+    (
+        Monitor {
+            params,
+            message_sender,
+        },
+        message_receiver,
+    )
+}
+
+/// Advertising data a monitored device must match.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern {
+    /// Index of the byte in the advertising data type where matching starts.
+    pub start_position: u8,
+    /// Advertising data type to match, such as `0xff` for manufacturer data.
+    pub ad_type: u8,
+    pub content: Vec<u8>,
+}
+
+/// Parameters of the monitor, exposed to BlueZ as properties.
+///
+/// The defaults leave every RSSI parameter unset, so BlueZ reports devices
+/// as soon as they match a pattern.
+#[derive(Clone, Debug)]
+pub struct Params {
+    /// RSSI in dBm below which a device is considered lost, 127 if unset.
+    pub rssi_low_threshold: i16,
+    /// RSSI in dBm above which a device is considered found, 127 if unset.
+    pub rssi_high_threshold: i16,
+    /// Seconds below `rssi_low_threshold` before a device is lost, 0 if unset.
+    pub rssi_low_timeout: u16,
+    /// Seconds above `rssi_high_threshold` before a device is found, 0 if unset.
+    pub rssi_high_timeout: u16,
+    /// Interval between reported RSSI samples in units of 100ms, 256 if unset.
+    pub rssi_sampling_period: u16,
+    /// A device is monitored when it matches any of these patterns.
+    pub patterns: Vec<Pattern>,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self {
+            rssi_low_threshold: 127,
+            rssi_high_threshold: 127,
+            rssi_low_timeout: 0,
+            rssi_high_timeout: 0,
+            rssi_sampling_period: 256,
+            patterns: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Message {
+    Activate,
+    DeviceFound { device: OwnedObjectPath },
+    DeviceLost { device: OwnedObjectPath },
+    Release,
+}
+
+pub struct Monitor {
+    params: Params,
+    pub(self) message_sender: mpsc::Sender<Message>,
+}
+
+#[zbus::interface(name = "org.bluez.AdvertisementMonitor1")]
+impl Monitor {
+    /// This gets called as a signal for a client to perform
+    /// clean-up when (1)a monitor cannot be activated after it
+    /// was exposed or (2)a monitor has been deactivated.
+    async fn release(&mut self) -> zbus::fdo::Result<()> {
+        tracing::debug!("release");
+
+        _ = self.message_sender.send(Message::Release).await;
+
+        Ok(())
+    }
+
+    /// After a monitor was exposed, this gets called as a
+    /// signal for client to get acknowledged when a monitor
+    /// has been activated, so the client can expect to receive
+    /// calls on DeviceFound() or DeviceLost().
+    async fn activate(&mut self) -> zbus::fdo::Result<()> {
+        tracing::debug!("activate");
+
+        _ = self.message_sender.send(Message::Activate).await;
+
+        Ok(())
+    }
+
+    /// This gets called to notify the client of finding the
+    /// targeted device. Once receiving the call, the client
+    /// should start to monitor the corresponding device to
+    /// retrieve the changes on RSSI and advertisement content.
+    async fn device_found(&mut self, device: OwnedObjectPath) -> zbus::fdo::Result<()> {
+        tracing::debug!(?device, "device_found");
+
+        _ = self
+            .message_sender
+            .send(Message::DeviceFound { device })
+            .await;
+
+        Ok(())
+    }
+
+    /// This gets called to notify the client of losing the
+    /// targeted device. Once receiving this call, the client
+    /// should stop monitoring the corresponding device.
+    async fn device_lost(&mut self, device: OwnedObjectPath) -> zbus::fdo::Result<()> {
+        tracing::debug!(?device, "device_lost");
+
+        _ = self
+            .message_sender
+            .send(Message::DeviceLost { device })
+            .await;
+
+        Ok(())
+    }
+
+    /// The type of the monitor.
+    #[zbus(property, name = "Type")]
+    fn monitor_type(&self) -> String {
+        "or_patterns".to_owned()
+    }
+
+    /// Used in conjunction with RSSILowTimeout to determine
+    /// whether a device becomes out-of-range.
+    #[zbus(property, name = "RSSILowThreshold")]
+    fn rssi_low_threshold(&self) -> i16 {
+        self.params.rssi_low_threshold
+    }
+
+    /// Used in conjunction with RSSIHighTimeout to determine
+    /// whether a device becomes in-range.
+    #[zbus(property, name = "RSSIHighThreshold")]
+    fn rssi_high_threshold(&self) -> i16 {
+        self.params.rssi_high_threshold
+    }
+
+    /// The time it takes to consider a device as out-of-range.
+    #[zbus(property, name = "RSSILowTimeout")]
+    fn rssi_low_timeout(&self) -> u16 {
+        self.params.rssi_low_timeout
+    }
+
+    /// The time it takes to consider a device as in-range.
+    #[zbus(property, name = "RSSIHighTimeout")]
+    fn rssi_high_timeout(&self) -> u16 {
+        self.params.rssi_high_timeout
+    }
+
+    /// Grouping rules on how to propagate the received
+    /// advertisement packets to the client.
+    #[zbus(property, name = "RSSISamplingPeriod")]
+    fn rssi_sampling_period(&self) -> u16 {
+        self.params.rssi_sampling_period
+    }
+
+    /// If the Type property is set to "or_patterns", then
+    /// this property must exist and have at least one entry
+    /// in the array.
+    #[zbus(property)]
+    fn patterns(&self) -> Vec<(u8, u8, Vec<u8>)> {
+        self.params
+            .patterns
+            .iter()
+            .map(|pattern| {
+                (
+                    pattern.start_position,
+                    pattern.ad_type,
+                    pattern.content.clone(),
+                )
+            })
+            .collect()
+    }
+}
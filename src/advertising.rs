@@ -0,0 +1,82 @@
+//! Types for the data devices put in their advertisements.
+
+use std::fmt;
+
+/// Bluetooth SIG assigned company identifier, as used for manufacturer specific data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CompanyId(pub u16);
+
+impl CompanyId {
+	/// Name of the company, for a small set of commonly seen vendors.
+	pub fn name(self) -> Option<&'static str> {
+		let name = match self.0 {
+			0x0000 => "Ericsson",
+			0x0001 => "Nokia",
+			0x0002 => "Intel",
+			0x0006 => "Microsoft",
+			0x000A => "Qualcomm Technologies International",
+			0x000D => "Texas Instruments",
+			0x000F => "Broadcom",
+			0x001D => "Qualcomm",
+			0x0030 => "STMicroelectronics",
+			0x004C => "Apple",
+			0x0059 => "Nordic Semiconductor",
+			0x0075 => "Samsung Electronics",
+			0x0087 => "Garmin",
+			0x00E0 => "Google",
+			0x012D => "Sony",
+			0x0131 => "Cypress Semiconductor",
+			0x0157 => "Anhui Huami",
+			0x0171 => "Amazon",
+			0x02E5 => "Espressif",
+			0x038F => "Xiaomi",
+			0x0499 => "Ruuvi Innovations",
+			_ => return None,
+		};
+		Some(name)
+	}
+}
+
+impl From<u16> for CompanyId {
+	fn from(id: u16) -> Self {
+		Self(id)
+	}
+}
+
+impl fmt::Display for CompanyId {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.name() {
+			Some(name) => write!(f, "{name} (0x{:04X})", self.0),
+			None => write!(f, "0x{:04X}", self.0),
+		}
+	}
+}
+
+/// The Flags AD type of an advertisement.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct AdvertisingFlags(pub u8);
+
+impl AdvertisingFlags {
+	pub const LIMITED_DISCOVERABLE: Self = Self(1 << 0);
+	pub const GENERAL_DISCOVERABLE: Self = Self(1 << 1);
+	pub const BR_EDR_NOT_SUPPORTED: Self = Self(1 << 2);
+	/// Simultaneous LE and BR/EDR to the same device, supported by the controller.
+	pub const SIMULTANEOUS_LE_BR_EDR_CONTROLLER: Self = Self(1 << 3);
+	/// Simultaneous LE and BR/EDR to the same device, supported by the host.
+	pub const SIMULTANEOUS_LE_BR_EDR_HOST: Self = Self(1 << 4);
+
+	pub const fn contains(self, other: Self) -> bool {
+		self.0 & other.0 == other.0
+	}
+
+	/// Whether the device is in either discoverable mode.
+	pub const fn is_discoverable(self) -> bool {
+		self.0 & (Self::LIMITED_DISCOVERABLE.0 | Self::GENERAL_DISCOVERABLE.0) != 0
+	}
+}
+
+impl From<u8> for AdvertisingFlags {
+	fn from(flags: u8) -> Self {
+		Self(flags)
+	}
+}
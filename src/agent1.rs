@@ -3,22 +3,160 @@
 
 //! Integrations for creating bluez agents.
 
-use futures_util::SinkExt;
+use std::future::Future;
+
 use tokio::sync::{mpsc, oneshot};
-use zbus::zvariant::OwnedObjectPath;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath};
+
+use crate::agent_manager1::AgentManager1Proxy;
 
+/// Capacity of the message channel created by [`create`].
+pub const DEFAULT_CAPACITY: usize = 1;
+
+/// Creates an agent with a message channel of [`DEFAULT_CAPACITY`].
+///
+/// See [`create_with_capacity`] for how a full channel is handled.
 pub fn create() -> (Agent, mpsc::Receiver<Message>) {
-    let (message_sender, message_receiver) = tokio::sync::mpsc::channel(1);
+    create_with_capacity(DEFAULT_CAPACITY)
+}
+
+/// Creates an agent whose message channel buffers up to `capacity` messages.
+///
+/// The channel is bounded: once it is full, the agent waits for the receiver
+/// to make room before handing over the next message, and BlueZ waits for the
+/// reply to its method call meanwhile. No message is dropped, so a slow handler
+/// slows down the pairing instead of growing memory. Messages are dropped only
+/// once the receiver itself is dropped.
+///
+/// # Panics
+///
+/// Panics if `capacity` is 0.
+pub fn create_with_capacity(capacity: usize) -> (Agent, mpsc::Receiver<Message>) {
+    let (message_sender, message_receiver) = tokio::sync::mpsc::channel(capacity);
+
+    (
+        Agent {
+            message_sender,
+            policy: None,
+            pin_code_shown: None,
+        },
+        message_receiver,
+    )
+}
+
+/// Exports `agent` at `path`, registers it with `manager` and makes it the default agent.
+///
+/// If a step fails, the steps that already succeeded are undone. The agent is
+/// unregistered and removed from the bus when the returned guard is dropped.
+pub async fn register(
+    connection: &zbus::Connection,
+    manager: &AgentManager1Proxy<'_>,
+    agent: Agent,
+    path: ObjectPath<'_>,
+    capability: Capability,
+) -> zbus::Result<Registration> {
+    crate::serve_at(connection, path.as_str(), agent).await?;
+
+    let registered = async {
+        // Owned copy of the manager, so the guard can unregister on drop.
+        let owned_manager = AgentManager1Proxy::builder(connection)
+            .destination(manager.inner().destination().to_owned())?
+            .path(manager.inner().path().to_owned())?
+            .build()
+            .await?;
+
+        manager
+            .register_agent(&path, <&'static str>::from(capability))
+            .await?;
+
+        if let Err(why) = manager.request_default_agent(&path).await {
+            _ = manager.unregister_agent(&path).await;
+            return Err(why);
+        }
+
+        Ok(Registration {
+            connection: connection.clone(),
+            manager: owned_manager,
+            path: path.to_owned().into(),
+            unregistered: false,
+        })
+    };
+
+    match registered.await {
+        Ok(registration) => Ok(registration),
+        Err(why) => {
+            _ = connection.object_server().remove::<Agent, _>(&path).await;
+            Err(why)
+        }
+    }
+}
+
+/// An agent exported on the bus and registered with BlueZ.
+///
+/// The agent is unregistered when this is dropped, which requires a tokio runtime.
+/// Use [`Registration::unregister`] to observe errors instead.
+#[derive(Debug)]
+pub struct Registration {
+    connection: zbus::Connection,
+    manager: AgentManager1Proxy<'static>,
+    path: OwnedObjectPath,
+    unregistered: bool,
+}
+
+impl Registration {
+    pub fn path(&self) -> OwnedObjectPath {
+        self.path.clone()
+    }
 
-    (Agent { message_sender }, message_receiver)
+    /// Unregisters the agent from BlueZ and removes it from the bus.
+    pub async fn unregister(mut self) -> zbus::Result<()> {
+        self.unregistered = true;
+        unregister_agent(&self.connection, &self.manager, &self.path).await
+    }
 }
 
-#[derive(Clone, Copy, Debug)]
+impl Drop for Registration {
+    fn drop(&mut self) {
+        if self.unregistered {
+            return;
+        }
+
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            tracing::warn!("no tokio runtime available to unregister the agent");
+            return;
+        };
+        let connection = self.connection.clone();
+        let manager = self.manager.clone();
+        let path = self.path.clone();
+        handle.spawn(async move {
+            if let Err(why) = unregister_agent(&connection, &manager, &path).await {
+                tracing::warn!(?why, "failed to unregister agent");
+            }
+        });
+    }
+}
+
+async fn unregister_agent(
+    connection: &zbus::Connection,
+    manager: &AgentManager1Proxy<'_>,
+    path: &OwnedObjectPath,
+) -> zbus::Result<()> {
+    let unregistered = manager.unregister_agent(path).await;
+    _ = connection
+        .object_server()
+        .remove::<Agent, _>(path.as_str())
+        .await;
+    unregistered
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum Capability {
     DisplayOnly = 0x00,
     DisplayYesNo = 0x01,
     KeyboardOnly = 0x02,
     NoInputNoOutput = 0x03,
+    /// This is what BlueZ assumes when an agent registers with an empty capability.
+    #[default]
     KeyboardDisplay = 0x04,
 }
 
@@ -34,11 +172,47 @@ impl From<Capability> for &'static str {
     }
 }
 
+impl TryFrom<&str> for Capability {
+    type Error = InvalidCapability;
+
+    fn try_from(capability: &str) -> Result<Self, Self::Error> {
+        match capability {
+            "DisplayOnly" => Ok(Capability::DisplayOnly),
+            "DisplayYesNo" => Ok(Capability::DisplayYesNo),
+            "KeyboardOnly" => Ok(Capability::KeyboardOnly),
+            "NoInputNoOutput" => Ok(Capability::NoInputNoOutput),
+            "KeyboardDisplay" => Ok(Capability::KeyboardDisplay),
+            _ => Err(InvalidCapability(capability.to_owned())),
+        }
+    }
+}
+
+impl std::str::FromStr for Capability {
+    type Err = InvalidCapability;
+
+    fn from_str(capability: &str) -> Result<Self, Self::Err> {
+        Capability::try_from(capability)
+    }
+}
+
+/// Error returned when parsing a string that is not a BlueZ agent capability.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidCapability(pub String);
+
+impl std::fmt::Display for InvalidCapability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid agent capability: {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidCapability {}
+
 #[derive(Debug)]
 pub enum Message {
     AuthorizeService {
         device: OwnedObjectPath,
         uuid: String,
+        response: ConfirmationResponder,
     },
     Cancel,
     DisplayPasskey {
@@ -46,32 +220,221 @@ pub enum Message {
         passkey: u32,
         entered: u16,
     },
+    /// Show `pincode` until `dismissal` resolves.
     DisplayPinCode {
         device: OwnedObjectPath,
         pincode: String,
+        dismissal: PinCodeDismissal,
     },
     Release,
     RequestAuthorization {
         device: OwnedObjectPath,
-        response: oneshot::Sender<bool>,
+        /// Alias of the device, resolved when the request was received.
+        alias: Option<String>,
+        response: ConfirmationResponder,
     },
     RequestConfirmation {
         device: OwnedObjectPath,
+        /// Alias of the device, resolved when the request was received.
+        alias: Option<String>,
         passkey: u32,
-        response: oneshot::Sender<bool>,
+        response: ConfirmationResponder,
     },
     RequestPasskey {
         device: OwnedObjectPath,
-        response: oneshot::Sender<Option<u32>>,
+        /// Alias of the device, resolved when the request was received.
+        alias: Option<String>,
+        response: PasskeyResponder,
     },
     RequestPinCode {
         device: OwnedObjectPath,
-        response: oneshot::Sender<Option<String>>,
+        /// Alias of the device, resolved when the request was received.
+        alias: Option<String>,
+        response: PinCodeResponder,
     },
 }
 
+/// A request from BlueZ that an agent policy can decide on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RequestKind {
+    AuthorizeService { uuid: String },
+    RequestAuthorization,
+    RequestConfirmation { passkey: u32 },
+    RequestPasskey,
+    RequestPinCode,
+}
+
+/// Decision of an agent policy, see [`Agent::with_policy`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Policy {
+    /// Accept the request without asking the user.
+    ///
+    /// Requests that need a value from the user, such as `RequestPasskey`,
+    /// are still sent to the channel.
+    Allow,
+    /// Reply with `org.bluez.Error.Rejected` without asking the user.
+    Reject,
+    /// Send the request to the channel as usual.
+    #[default]
+    AskUser,
+}
+
+type PolicyFn = dyn Fn(&RequestKind, &ObjectPath<'_>) -> Policy + Send + Sync;
+
+/// Renders the passkey of a `DisplayPasskey` request along with the typing
+/// progress reported by the remote keyboard.
+///
+/// The passkey is always shown as 6 digits, zero-padded at the start.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PasskeyDisplay {
+    pub passkey: u32,
+    pub entered: u16,
+}
+
+impl PasskeyDisplay {
+    pub fn new(passkey: u32, entered: u16) -> Self {
+        Self { passkey, entered }
+    }
+
+    /// The zero-padded 6-digit passkey.
+    pub fn digits(&self) -> String {
+        format!("{:06}", self.passkey % 1_000_000)
+    }
+
+    /// Splits the digits into those already typed on the remote side and
+    /// those remaining, for interfaces that highlight the progress.
+    pub fn split(&self) -> (String, String) {
+        let digits = self.digits();
+        let entered = usize::from(self.entered).min(digits.len());
+        let (typed, remaining) = digits.split_at(entered);
+        (typed.to_owned(), remaining.to_owned())
+    }
+}
+
+/// Shows the passkey with the digits typed so far masked, such as `***456`.
+impl std::fmt::Display for PasskeyDisplay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (typed, remaining) = self.split();
+        for _ in typed.chars() {
+            f.write_str("*")?;
+        }
+        f.write_str(&remaining)
+    }
+}
+
+/// Errors returned to BlueZ, in the `org.bluez.Error` namespace.
+#[derive(Debug, zbus::DBusError)]
+#[zbus(prefix = "org.bluez.Error")]
+pub enum AgentError {
+    #[zbus(error)]
+    ZBus(zbus::Error),
+    /// The request was rejected by the agent.
+    Rejected(String),
+    /// The request was dropped without a response.
+    Canceled(String),
+}
+
+/// Reply to a `RequestConfirmation`, `RequestAuthorization` or `AuthorizeService` request.
+#[derive(Debug)]
+pub struct ConfirmationResponder(oneshot::Sender<Option<()>>);
+
+impl ConfirmationResponder {
+    pub fn accept(self) {
+        _ = self.0.send(Some(()));
+    }
+
+    /// Replies with `org.bluez.Error.Rejected`.
+    pub fn reject(self) {
+        _ = self.0.send(None);
+    }
+}
+
+/// Reply to a `RequestPasskey` request.
+#[derive(Debug)]
+pub struct PasskeyResponder(oneshot::Sender<Option<u32>>);
+
+impl PasskeyResponder {
+    /// Replies with `passkey`, which should be between 0 and 999999.
+    pub fn accept(self, passkey: u32) {
+        _ = self.0.send(Some(passkey));
+    }
+
+    /// Replies with `org.bluez.Error.Rejected`.
+    pub fn reject(self) {
+        _ = self.0.send(None);
+    }
+}
+
+/// Signaled when a PIN code shown for `DisplayPinCode` no longer needs to be
+/// displayed.
+///
+/// BlueZ does not wait for the PIN code to be typed, so the display has to stay
+/// up until it calls `Cancel`, which happens once pairing finishes or fails, or
+/// until the agent is released.
+#[derive(Debug)]
+pub struct PinCodeDismissal(oneshot::Receiver<()>);
+
+impl PinCodeDismissal {
+    /// Resolves once the PIN code should be hidden. This also happens when the
+    /// agent is dropped, or when a new `DisplayPinCode` replaces this one.
+    pub async fn dismissed(self) {
+        _ = self.0.await;
+    }
+
+    /// Whether the PIN code should be hidden already, without waiting.
+    pub fn is_dismissed(&mut self) -> bool {
+        !matches!(self.0.try_recv(), Err(oneshot::error::TryRecvError::Empty))
+    }
+}
+
+/// Reply to a `RequestPinCode` request.
+#[derive(Debug)]
+pub struct PinCodeResponder(oneshot::Sender<Option<String>>);
+
+impl PinCodeResponder {
+    /// Replies with `pin_code`, which should be 1 to 16 alphanumeric characters.
+    pub fn accept(self, pin_code: String) {
+        _ = self.0.send(Some(pin_code));
+    }
+
+    /// Replies with `org.bluez.Error.Rejected`.
+    pub fn reject(self) {
+        _ = self.0.send(None);
+    }
+}
+
 pub struct Agent {
     pub(self) message_sender: mpsc::Sender<Message>,
+    policy: Option<Box<PolicyFn>>,
+    /// Dismisses the PIN code of the last `DisplayPinCode` when signaled.
+    pin_code_shown: Option<oneshot::Sender<()>>,
+}
+
+impl Agent {
+    /// Evaluates `policy` for every request before it is sent to the channel,
+    /// given the request and the path of the device it is about.
+    pub fn with_policy(
+        mut self,
+        policy: impl Fn(&RequestKind, &ObjectPath<'_>) -> Policy + Send + Sync + 'static,
+    ) -> Self {
+        self.policy = Some(Box::new(policy));
+        self
+    }
+
+    fn policy(&self, kind: &RequestKind, device: &OwnedObjectPath) -> Policy {
+        let policy = self
+            .policy
+            .as_ref()
+            .map_or(Policy::AskUser, |policy| policy(kind, device));
+        tracing::debug!(?kind, ?device, ?policy, "agent policy");
+        policy
+    }
+
+    fn dismiss_pin_code(&mut self) {
+        if let Some(shown) = self.pin_code_shown.take() {
+            _ = shown.send(());
+        }
+    }
 }
 
 #[zbus::interface(name = "org.bluez.Agent1")]
@@ -82,10 +445,30 @@ impl Agent {
         &mut self,
         device: OwnedObjectPath,
         uuid: String,
-    ) -> zbus::fdo::Result<()> {
+    ) -> Result<(), AgentError> {
         tracing::debug!(?device, uuid, "authorize_service");
 
-        Ok(())
+        match self.policy(
+            &RequestKind::AuthorizeService { uuid: uuid.clone() },
+            &device,
+        ) {
+            Policy::Allow => return Ok(()),
+            Policy::Reject => return Err(rejected_by_policy()),
+            Policy::AskUser => (),
+        }
+
+        let (response, response_rx) = oneshot::channel();
+
+        _ = self
+            .message_sender
+            .send(Message::AuthorizeService {
+                device,
+                uuid,
+                response: ConfirmationResponder(response),
+            })
+            .await;
+
+        await_reply(response_rx).await
     }
 
     /// This method gets called to indicate that the agent request
@@ -93,6 +476,7 @@ impl Agent {
     async fn cancel(&mut self) -> zbus::fdo::Result<()> {
         tracing::debug!("cancel");
 
+        self.dismiss_pin_code();
         _ = self.message_sender.send(Message::Cancel).await;
 
         Ok(())
@@ -122,6 +506,15 @@ impl Agent {
     ) -> zbus::fdo::Result<()> {
         tracing::debug!(?device, passkey, entered, "display_passkey");
 
+        _ = self
+            .message_sender
+            .send(Message::DisplayPasskey {
+                device,
+                passkey,
+                entered,
+            })
+            .await;
+
         Ok(())
     }
 
@@ -150,12 +543,25 @@ impl Agent {
     ) -> zbus::fdo::Result<()> {
         tracing::debug!(?device, pin_code, "display_pin_code");
 
+        let (shown, dismissal) = oneshot::channel();
+        self.pin_code_shown = Some(shown);
+
+        _ = self
+            .message_sender
+            .send(Message::DisplayPinCode {
+                device,
+                pincode: pin_code,
+                dismissal: PinCodeDismissal(dismissal),
+            })
+            .await;
+
         Ok(())
     }
 
     async fn release(&mut self) -> zbus::fdo::Result<()> {
         tracing::debug!("release");
 
+        self.dismiss_pin_code();
         _ = self.message_sender.send(Message::Release).await;
 
         Ok(())
@@ -170,10 +576,32 @@ impl Agent {
     /// In the latter case, the
     /// device would not be connected to the adapter via
     /// Bluetooth yet.
-    async fn request_authorization(&mut self, device: OwnedObjectPath) -> zbus::fdo::Result<()> {
+    async fn request_authorization(
+        &mut self,
+        #[zbus(connection)] connection: &zbus::Connection,
+        device: OwnedObjectPath,
+    ) -> Result<(), AgentError> {
         tracing::debug!(?device, "request_authorization");
 
-        Ok(())
+        match self.policy(&RequestKind::RequestAuthorization, &device) {
+            Policy::Allow => return Ok(()),
+            Policy::Reject => return Err(rejected_by_policy()),
+            Policy::AskUser => (),
+        }
+
+        let alias = device_alias(connection, &device).await;
+        let (response, response_rx) = oneshot::channel();
+
+        _ = self
+            .message_sender
+            .send(Message::RequestAuthorization {
+                device,
+                alias,
+                response: ConfirmationResponder(response),
+            })
+            .await;
+
+        await_reply(response_rx).await
     }
 
     /// This method gets called when the service daemon
@@ -187,27 +615,32 @@ impl Agent {
     /// the value contains less than 6 digits.
     async fn request_confirmation(
         &mut self,
+        #[zbus(connection)] connection: &zbus::Connection,
         device: OwnedObjectPath,
         passkey: u32,
-    ) -> zbus::fdo::Result<()> {
+    ) -> Result<(), AgentError> {
         tracing::debug!(?device, passkey, "request_confirmation");
 
-        let (response, response_rx) = oneshot::channel::<bool>();
+        match self.policy(&RequestKind::RequestConfirmation { passkey }, &device) {
+            Policy::Allow => return Ok(()),
+            Policy::Reject => return Err(rejected_by_policy()),
+            Policy::AskUser => (),
+        }
+
+        let alias = device_alias(connection, &device).await;
+        let (response, response_rx) = oneshot::channel();
 
         _ = self
             .message_sender
             .send(Message::RequestConfirmation {
                 device,
+                alias,
                 passkey,
-                response,
+                response: ConfirmationResponder(response),
             })
             .await;
 
-        match response_rx.await {
-            Ok(true) => Ok(()),
-            Ok(false) => Err(zbus::fdo::Error::Failed("cancelled".to_string())),
-            Err(why) => Err(zbus::fdo::Error::Failed(why.to_string())),
-        }
+        await_reply(response_rx).await
     }
 
     /// This method gets called when the service daemon
@@ -215,21 +648,30 @@ impl Agent {
     ///
     /// The return value should be a numeric value
     /// between 0-999999.
-    async fn request_passkey(&mut self, device: OwnedObjectPath) -> zbus::fdo::Result<u32> {
+    async fn request_passkey(
+        &mut self,
+        #[zbus(connection)] connection: &zbus::Connection,
+        device: OwnedObjectPath,
+    ) -> Result<u32, AgentError> {
         tracing::debug!(?device, "request_passkey");
 
-        let (response, response_rx) = oneshot::channel::<Option<u32>>();
+        if self.policy(&RequestKind::RequestPasskey, &device) == Policy::Reject {
+            return Err(rejected_by_policy());
+        }
+
+        let alias = device_alias(connection, &device).await;
+        let (response, response_rx) = oneshot::channel();
 
         _ = self
             .message_sender
-            .send(Message::RequestPasskey { device, response })
+            .send(Message::RequestPasskey {
+                device,
+                alias,
+                response: PasskeyResponder(response),
+            })
             .await;
 
-        match response_rx.await {
-            Ok(Some(passkey)) => Ok(passkey),
-            Ok(None) => Err(zbus::fdo::Error::Failed("cancelled".to_string())),
-            Err(why) => Err(zbus::fdo::Error::Failed(why.to_string())),
-        }
+        await_reply(response_rx).await
     }
 
     /// This method gets called when the service daemon
@@ -237,20 +679,227 @@ impl Agent {
     ///
     /// The return value should be a string of 1-16 characters
     /// length. The string can be alphanumeric.
-    async fn request_pin_code(&mut self, device: OwnedObjectPath) -> zbus::fdo::Result<String> {
+    async fn request_pin_code(
+        &mut self,
+        #[zbus(connection)] connection: &zbus::Connection,
+        device: OwnedObjectPath,
+    ) -> Result<String, AgentError> {
         tracing::debug!(?device, "request_pin_code");
 
-        let (response, response_rx) = oneshot::channel::<Option<String>>();
+        if self.policy(&RequestKind::RequestPinCode, &device) == Policy::Reject {
+            return Err(rejected_by_policy());
+        }
+
+        let alias = device_alias(connection, &device).await;
+        let (response, response_rx) = oneshot::channel();
 
         _ = self
             .message_sender
-            .send(Message::RequestPinCode { device, response })
+            .send(Message::RequestPinCode {
+                device,
+                alias,
+                response: PinCodeResponder(response),
+            })
             .await;
 
-        match response_rx.await {
-            Ok(Some(pin_code)) => Ok(pin_code),
-            Ok(None) => Err(zbus::fdo::Error::Failed("cancelled".to_string())),
-            Err(why) => Err(zbus::fdo::Error::Failed(why.to_string())),
+        await_reply(response_rx).await
+    }
+}
+
+/// Waits for a responder to be used, mapping a rejection or a dropped
+/// responder to the matching BlueZ error.
+async fn await_reply<T>(response_rx: oneshot::Receiver<Option<T>>) -> Result<T, AgentError> {
+    match response_rx.await {
+        Ok(Some(value)) => Ok(value),
+        Ok(None) => Err(AgentError::Rejected("rejected by agent".to_owned())),
+        Err(_) => Err(AgentError::Canceled("request dropped by agent".to_owned())),
+    }
+}
+
+fn rejected_by_policy() -> AgentError {
+    AgentError::Rejected("rejected by agent policy".to_owned())
+}
+
+/// Looks up the alias of `device`, which BlueZ falls back to the name or address for.
+async fn device_alias(connection: &zbus::Connection, device: &OwnedObjectPath) -> Option<String> {
+    let proxy = crate::device1::Device1Proxy::builder(connection)
+        .path(device.clone())
+        .ok()?
+        .build()
+        .await
+        .ok()?;
+
+    match proxy.alias().await {
+        Ok(alias) => Some(alias),
+        Err(why) => {
+            tracing::warn!(?device, ?why, "failed to resolve device alias");
+            None
+        }
+    }
+}
+
+/// Handles the requests of an agent, for use with [`run`].
+///
+/// Every method has a default: requests that need an answer are rejected and
+/// notifications are ignored, so implementors only override what they support.
+pub trait AgentHandler: Send {
+    /// Whether `device` may use the service `uuid`.
+    fn authorize_service(
+        &mut self,
+        _device: OwnedObjectPath,
+        _uuid: String,
+    ) -> impl Future<Output = bool> + Send {
+        async { false }
+    }
+
+    /// The request being displayed was canceled by BlueZ.
+    fn cancel(&mut self) -> impl Future<Output = ()> + Send {
+        async {}
+    }
+
+    /// Shows `passkey` while the remote keyboard types it, see [`PasskeyDisplay`].
+    fn display_passkey(
+        &mut self,
+        _device: OwnedObjectPath,
+        _passkey: PasskeyDisplay,
+    ) -> impl Future<Output = ()> + Send {
+        async {}
+    }
+
+    /// Shows `pin_code` for a legacy keyboard to type, see [`PinCodeDismissal`].
+    ///
+    /// Awaiting the dismissal here holds the following messages back until
+    /// the PIN code is hidden, the `Cancel` that dismissed it included.
+    fn display_pin_code(
+        &mut self,
+        _device: OwnedObjectPath,
+        _pin_code: String,
+        _dismissal: PinCodeDismissal,
+    ) -> impl Future<Output = ()> + Send {
+        async {}
+    }
+
+    /// The agent was unregistered, after which [`run`] returns.
+    fn release(&mut self) -> impl Future<Output = ()> + Send {
+        async {}
+    }
+
+    /// Whether to accept an incoming just-works pairing from `device`.
+    fn request_authorization(
+        &mut self,
+        _device: OwnedObjectPath,
+        _alias: Option<String>,
+    ) -> impl Future<Output = bool> + Send {
+        async { false }
+    }
+
+    /// Whether `passkey` matches the one shown on `device`.
+    fn request_confirmation(
+        &mut self,
+        _device: OwnedObjectPath,
+        _alias: Option<String>,
+        _passkey: u32,
+    ) -> impl Future<Output = bool> + Send {
+        async { false }
+    }
+
+    /// The passkey shown on `device`, or `None` to reject.
+    fn request_passkey(
+        &mut self,
+        _device: OwnedObjectPath,
+        _alias: Option<String>,
+    ) -> impl Future<Output = Option<u32>> + Send {
+        async { None }
+    }
+
+    /// The PIN code for `device`, or `None` to reject.
+    fn request_pin_code(
+        &mut self,
+        _device: OwnedObjectPath,
+        _alias: Option<String>,
+    ) -> impl Future<Output = Option<String>> + Send {
+        async { None }
+    }
+}
+
+/// Dispatches the messages of an agent to `handler` until the agent is
+/// released or its channel closes.
+///
+/// Dropping this future is safe: a request whose handler did not finish is
+/// answered with `org.bluez.Error.Canceled`, and messages that were not taken
+/// yet stay in `receiver`, so `run` can be called on it again.
+pub async fn run(receiver: &mut mpsc::Receiver<Message>, mut handler: impl AgentHandler) {
+    while let Some(message) = receiver.recv().await {
+        match message {
+            Message::AuthorizeService {
+                device,
+                uuid,
+                response,
+            } => {
+                if handler.authorize_service(device, uuid).await {
+                    response.accept();
+                } else {
+                    response.reject();
+                }
+            }
+            Message::Cancel => handler.cancel().await,
+            Message::DisplayPasskey {
+                device,
+                passkey,
+                entered,
+            } => {
+                handler
+                    .display_passkey(device, PasskeyDisplay::new(passkey, entered))
+                    .await
+            }
+            Message::DisplayPinCode {
+                device,
+                pincode,
+                dismissal,
+            } => handler.display_pin_code(device, pincode, dismissal).await,
+            Message::Release => {
+                handler.release().await;
+                return;
+            }
+            Message::RequestAuthorization {
+                device,
+                alias,
+                response,
+            } => {
+                if handler.request_authorization(device, alias).await {
+                    response.accept();
+                } else {
+                    response.reject();
+                }
+            }
+            Message::RequestConfirmation {
+                device,
+                alias,
+                passkey,
+                response,
+            } => {
+                if handler.request_confirmation(device, alias, passkey).await {
+                    response.accept();
+                } else {
+                    response.reject();
+                }
+            }
+            Message::RequestPasskey {
+                device,
+                alias,
+                response,
+            } => match handler.request_passkey(device, alias).await {
+                Some(passkey) => response.accept(passkey),
+                None => response.reject(),
+            },
+            Message::RequestPinCode {
+                device,
+                alias,
+                response,
+            } => match handler.request_pin_code(device, alias).await {
+                Some(pin_code) => response.accept(pin_code),
+                None => response.reject(),
+            },
         }
     }
 }
@@ -0,0 +1,97 @@
+//! Decoding of the GAP appearance reported in `org.bluez.Device1.Appearance`.
+
+/// External appearance of an LE device.
+///
+/// The value is a 10-bit category followed by a 6-bit subcategory. Specific
+/// subcategories get their own variant where they matter for presentation;
+/// other subcategories decode to the variant of their category.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Appearance {
+	Phone,
+	Computer,
+	Watch,
+	Clock,
+	Display,
+	RemoteControl,
+	EyeGlasses,
+	Tag,
+	Keyring,
+	MediaPlayer,
+	BarcodeScanner,
+	Thermometer,
+	HeartRateSensor,
+	BloodPressure,
+	/// A human interface device not covered by a more specific variant.
+	HumanInterfaceDevice,
+	Keyboard,
+	Mouse,
+	Joystick,
+	Gamepad,
+	DigitizerTablet,
+	Touchpad,
+	GlucoseMeter,
+	RunningWalkingSensor,
+	Cycling,
+	Sensor,
+	LightFixture,
+	AudioSink,
+	AudioSource,
+	/// A wearable audio device not covered by a more specific variant.
+	WearableAudioDevice,
+	Earbud,
+	Headset,
+	Headphones,
+	HearingAid,
+	Gaming,
+	PulseOximeter,
+	WeightScale,
+	/// A value without a dedicated variant, including the generic `0x0000`.
+	Unknown(u16),
+}
+
+impl From<u16> for Appearance {
+	fn from(appearance: u16) -> Self {
+		let category = appearance >> 6;
+		let subcategory = appearance & 0x3F;
+
+		match (category, subcategory) {
+			(0x001, _) => Self::Phone,
+			(0x002, _) => Self::Computer,
+			(0x003, _) => Self::Watch,
+			(0x004, _) => Self::Clock,
+			(0x005, _) => Self::Display,
+			(0x006, _) => Self::RemoteControl,
+			(0x007, _) => Self::EyeGlasses,
+			(0x008, _) => Self::Tag,
+			(0x009, _) => Self::Keyring,
+			(0x00A, _) => Self::MediaPlayer,
+			(0x00B, _) => Self::BarcodeScanner,
+			(0x00C, _) => Self::Thermometer,
+			(0x00D, _) => Self::HeartRateSensor,
+			(0x00E, _) => Self::BloodPressure,
+			(0x00F, 0x01) => Self::Keyboard,
+			(0x00F, 0x02) => Self::Mouse,
+			(0x00F, 0x03) => Self::Joystick,
+			(0x00F, 0x04) => Self::Gamepad,
+			(0x00F, 0x05) => Self::DigitizerTablet,
+			(0x00F, 0x09) => Self::Touchpad,
+			(0x00F, _) => Self::HumanInterfaceDevice,
+			(0x010, _) => Self::GlucoseMeter,
+			(0x011, _) => Self::RunningWalkingSensor,
+			(0x012, _) => Self::Cycling,
+			(0x015, _) => Self::Sensor,
+			(0x016, _) => Self::LightFixture,
+			(0x021, _) => Self::AudioSink,
+			(0x022, _) => Self::AudioSource,
+			(0x025, 0x01) => Self::Earbud,
+			(0x025, 0x02) => Self::Headset,
+			(0x025, 0x03) => Self::Headphones,
+			(0x025, _) => Self::WearableAudioDevice,
+			(0x029, _) => Self::HearingAid,
+			(0x02A, _) => Self::Gaming,
+			(0x031, _) => Self::PulseOximeter,
+			(0x032, _) => Self::WeightScale,
+			_ => Self::Unknown(appearance),
+		}
+	}
+}
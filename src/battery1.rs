@@ -29,3 +29,26 @@ trait Battery1 {
 	#[zbus(property)]
 	fn source(&self) -> zbus::Result<String>;
 }
+
+impl<'p> Battery1Proxy<'p> {
+	/// Stream of battery levels in percent, starting with the current level.
+	pub async fn percentage_changes(&self) -> impl futures_util::Stream<Item = u8> + 'p {
+		use crate::changes::PropertyStreamExt;
+
+		self.receive_percentage_changed().await.values()
+	}
+
+	/// Yields the battery level once when it drops below `threshold` percent,
+	/// or right away if it already is, and then ends.
+	pub async fn watch_low_battery(
+		&self,
+		threshold: u8,
+	) -> impl futures_util::Stream<Item = u8> + 'p {
+		use futures_util::StreamExt;
+
+		self.percentage_changes()
+			.await
+			.filter(move |percentage| futures_util::future::ready(*percentage < threshold))
+			.take(1)
+	}
+}
@@ -0,0 +1,63 @@
+//! Decoding of `org.freedesktop.DBus.Properties.PropertiesChanged` payloads.
+
+use futures_util::{Stream, StreamExt};
+use zbus::zvariant::{OwnedValue, Value};
+
+/// A changed property of an `org.bluez.Device1` object.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeviceChange {
+	Alias(String),
+	Blocked(bool),
+	Connected(bool),
+	Name(String),
+	Paired(bool),
+	Rssi(i16),
+	ServicesResolved(bool),
+	Trusted(bool),
+	TxPower(i16),
+}
+
+impl DeviceChange {
+	/// Decodes a single changed property, returning `None` for properties
+	/// that are not covered by this enum or carry an unexpected type.
+	pub fn decode(name: &str, value: &Value<'_>) -> Option<Self> {
+		match name {
+			"Alias" => decode(value).map(Self::Alias),
+			"Blocked" => decode(value).map(Self::Blocked),
+			"Connected" => decode(value).map(Self::Connected),
+			"Name" => decode(value).map(Self::Name),
+			"Paired" => decode(value).map(Self::Paired),
+			"RSSI" => decode(value).map(Self::Rssi),
+			"ServicesResolved" => decode(value).map(Self::ServicesResolved),
+			"Trusted" => decode(value).map(Self::Trusted),
+			"TxPower" => decode(value).map(Self::TxPower),
+			_ => None,
+		}
+	}
+}
+
+/// Decoding of the streams returned by the generated `receive_*_changed` methods.
+pub trait PropertyStreamExt<'a, T> {
+	/// Maps every change to the new value, skipping values that fail to decode.
+	fn values(self) -> impl Stream<Item = T> + 'a;
+}
+
+impl<'a, T> PropertyStreamExt<'a, T> for zbus::proxy::PropertyStream<'a, T>
+where
+	T: TryFrom<OwnedValue> + Unpin + 'a,
+	T::Error: Into<zbus::Error>,
+{
+	fn values(self) -> impl Stream<Item = T> + 'a {
+		self.filter_map(|change| async move { change.get().await.ok() })
+	}
+}
+
+pub(crate) fn decode<T>(value: &Value<'_>) -> Option<T>
+where
+	T: TryFrom<OwnedValue>,
+{
+	value
+		.try_to_owned()
+		.ok()
+		.and_then(|value| T::try_from(value).ok())
+}
@@ -0,0 +1,171 @@
+//! Decoding of the BR/EDR Class of Device reported in `org.bluez.Device1.Class`.
+
+/// Major device class, from bits 8 to 12 of the Class of Device.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MajorClass {
+	Miscellaneous,
+	Computer,
+	Phone,
+	NetworkAccessPoint,
+	AudioVideo,
+	Peripheral,
+	Imaging,
+	Wearable,
+	Toy,
+	Health,
+	Uncategorized,
+	/// A value reserved by the specification.
+	Reserved(u8),
+}
+
+impl From<u8> for MajorClass {
+	fn from(major: u8) -> Self {
+		match major {
+			0x00 => Self::Miscellaneous,
+			0x01 => Self::Computer,
+			0x02 => Self::Phone,
+			0x03 => Self::NetworkAccessPoint,
+			0x04 => Self::AudioVideo,
+			0x05 => Self::Peripheral,
+			0x06 => Self::Imaging,
+			0x07 => Self::Wearable,
+			0x08 => Self::Toy,
+			0x09 => Self::Health,
+			0x1F => Self::Uncategorized,
+			major => Self::Reserved(major),
+		}
+	}
+}
+
+/// Minor device class, whose meaning depends on the major class.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MinorClass {
+	Uncategorized,
+	// Computer
+	Desktop,
+	Server,
+	Laptop,
+	Handheld,
+	PalmSize,
+	Watch,
+	Tablet,
+	// Phone
+	Cellular,
+	Cordless,
+	Smartphone,
+	Modem,
+	// Audio/Video
+	Headset,
+	HandsFree,
+	Microphone,
+	Loudspeaker,
+	Headphones,
+	PortableAudio,
+	CarAudio,
+	SetTopBox,
+	HiFi,
+	VideoCamera,
+	Camcorder,
+	VideoMonitor,
+	VideoDisplayAndLoudspeaker,
+	VideoConferencing,
+	GamingToy,
+	// Peripheral
+	Keyboard,
+	PointingDevice,
+	KeyboardPointingDevice,
+	Joystick,
+	Gamepad,
+	RemoteControl,
+	DigitizerTablet,
+	/// A minor class without a dedicated variant, as the raw 6 bits.
+	Other(u8),
+}
+
+/// Major service classes, from bits 13 to 23 of the Class of Device.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ServiceClasses(pub u16);
+
+impl ServiceClasses {
+	pub const LIMITED_DISCOVERABLE: Self = Self(1 << 0);
+	pub const LE_AUDIO: Self = Self(1 << 1);
+	pub const POSITIONING: Self = Self(1 << 3);
+	pub const NETWORKING: Self = Self(1 << 4);
+	pub const RENDERING: Self = Self(1 << 5);
+	pub const CAPTURING: Self = Self(1 << 6);
+	pub const OBJECT_TRANSFER: Self = Self(1 << 7);
+	pub const AUDIO: Self = Self(1 << 8);
+	pub const TELEPHONY: Self = Self(1 << 9);
+	pub const INFORMATION: Self = Self(1 << 10);
+
+	pub const fn contains(self, other: Self) -> bool {
+		self.0 & other.0 == other.0
+	}
+}
+
+/// A decoded Class of Device.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeviceClass {
+	pub major: MajorClass,
+	/// Raw minor device class bits, see [`DeviceClass::minor_class`].
+	pub minor: u8,
+	pub services: ServiceClasses,
+}
+
+impl DeviceClass {
+	/// Decodes the minor device class according to the major class.
+	pub fn minor_class(&self) -> MinorClass {
+		match (self.major, self.minor) {
+			(_, 0x00) => MinorClass::Uncategorized,
+			(MajorClass::Computer, 0x01) => MinorClass::Desktop,
+			(MajorClass::Computer, 0x02) => MinorClass::Server,
+			(MajorClass::Computer, 0x03) => MinorClass::Laptop,
+			(MajorClass::Computer, 0x04) => MinorClass::Handheld,
+			(MajorClass::Computer, 0x05) => MinorClass::PalmSize,
+			(MajorClass::Computer, 0x06) => MinorClass::Watch,
+			(MajorClass::Computer, 0x07) => MinorClass::Tablet,
+			(MajorClass::Phone, 0x01) => MinorClass::Cellular,
+			(MajorClass::Phone, 0x02) => MinorClass::Cordless,
+			(MajorClass::Phone, 0x03) => MinorClass::Smartphone,
+			(MajorClass::Phone, 0x04) => MinorClass::Modem,
+			(MajorClass::AudioVideo, 0x01) => MinorClass::Headset,
+			(MajorClass::AudioVideo, 0x02) => MinorClass::HandsFree,
+			(MajorClass::AudioVideo, 0x04) => MinorClass::Microphone,
+			(MajorClass::AudioVideo, 0x05) => MinorClass::Loudspeaker,
+			(MajorClass::AudioVideo, 0x06) => MinorClass::Headphones,
+			(MajorClass::AudioVideo, 0x07) => MinorClass::PortableAudio,
+			(MajorClass::AudioVideo, 0x08) => MinorClass::CarAudio,
+			(MajorClass::AudioVideo, 0x09) => MinorClass::SetTopBox,
+			(MajorClass::AudioVideo, 0x0A) => MinorClass::HiFi,
+			(MajorClass::AudioVideo, 0x0C) => MinorClass::VideoCamera,
+			(MajorClass::AudioVideo, 0x0D) => MinorClass::Camcorder,
+			(MajorClass::AudioVideo, 0x0E) => MinorClass::VideoMonitor,
+			(MajorClass::AudioVideo, 0x0F) => MinorClass::VideoDisplayAndLoudspeaker,
+			(MajorClass::AudioVideo, 0x10) => MinorClass::VideoConferencing,
+			(MajorClass::AudioVideo, 0x12) => MinorClass::GamingToy,
+			// The peripheral minor class is split into a keyboard/pointing
+			// field in the top two bits and a device type in the lower four.
+			(MajorClass::Peripheral, minor) => match (minor >> 4, minor & 0x0F) {
+				(0b01, 0x00) => MinorClass::Keyboard,
+				(0b10, 0x00) => MinorClass::PointingDevice,
+				(0b11, 0x00) => MinorClass::KeyboardPointingDevice,
+				(_, 0x01) => MinorClass::Joystick,
+				(_, 0x02) => MinorClass::Gamepad,
+				(_, 0x03) => MinorClass::RemoteControl,
+				(_, 0x05) => MinorClass::DigitizerTablet,
+				_ => MinorClass::Other(minor),
+			},
+			(_, minor) => MinorClass::Other(minor),
+		}
+	}
+}
+
+impl From<u32> for DeviceClass {
+	fn from(class: u32) -> Self {
+		Self {
+			major: MajorClass::from(((class >> 8) & 0x1F) as u8),
+			minor: ((class >> 2) & 0x3F) as u8,
+			services: ServiceClasses(((class >> 13) & 0x7FF) as u16),
+		}
+	}
+}
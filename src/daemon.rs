@@ -0,0 +1,33 @@
+//! Tracking whether `bluetoothd` is running.
+
+use futures_util::{Stream, StreamExt};
+
+/// Whether the `org.bluez` name currently has an owner on the bus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ServiceState {
+	/// `bluetoothd` exited. Proxies and registered objects such as agents are
+	/// no longer known to BlueZ.
+	Stopped,
+	/// `bluetoothd` (re)started. Agents and applications must be registered
+	/// again and adapters re-enumerated.
+	Started,
+}
+
+/// Streams the state of `bluetoothd` every time the owner of `org.bluez` changes.
+pub async fn watch_service(
+	connection: &zbus::Connection,
+) -> zbus::Result<impl Stream<Item = ServiceState>> {
+	let dbus = zbus::fdo::DBusProxy::new(connection).await?;
+	let name_owner_changed = dbus
+		.receive_name_owner_changed_with_args(&[(0, "org.bluez")])
+		.await?;
+
+	Ok(name_owner_changed.filter_map(|signal| async move {
+		let args = signal.args().ok()?;
+		if args.new_owner().is_some() {
+			Some(ServiceState::Started)
+		} else {
+			Some(ServiceState::Stopped)
+		}
+	}))
+}
@@ -127,6 +127,17 @@ trait Device1 {
 	#[zbus(property)]
 	fn services_resolved(&self) -> zbus::Result<bool>;
 
+	/// Sets property
+	#[zbus(property)]
+	fn sets(
+		&self,
+	) -> zbus::Result<
+		std::collections::HashMap<
+			zbus::zvariant::OwnedObjectPath,
+			std::collections::HashMap<String, zbus::zvariant::OwnedValue>,
+		>,
+	>;
+
 	/// Trusted property
 	#[zbus(property)]
 	fn trusted(&self) -> zbus::Result<bool>;
@@ -147,3 +158,13 @@ trait Device1 {
 	#[zbus(property)]
 	fn set_wake_allowed(&self, value: bool) -> zbus::Result<()>;
 }
+
+/// Membership of a device in a coordinated set, such as the left and right
+/// earbuds of an LE Audio pair, from the `Sets` property.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeviceSet {
+	/// Path of the `org.bluez.DeviceSet1` object shared by the set members.
+	pub path: zbus::zvariant::OwnedObjectPath,
+	/// Rank of the device within the set, if reported.
+	pub rank: Option<u8>,
+}
@@ -0,0 +1,360 @@
+//! Typed helpers for adapter discovery.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_util::{Stream, StreamExt};
+use zbus::zvariant::{OwnedObjectPath, Value};
+
+use crate::{adapter1, BluetoothDevice, BluezError, Uuid};
+
+/// Transport to restrict discovery to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Transport {
+	/// Interleaved scan, or BR/EDR inquiry if the controller does not support LE.
+	#[default]
+	Auto,
+	BrEdr,
+	Le,
+}
+
+impl From<Transport> for &'static str {
+	fn from(transport: Transport) -> &'static str {
+		match transport {
+			Transport::Auto => "auto",
+			Transport::BrEdr => "bredr",
+			Transport::Le => "le",
+		}
+	}
+}
+
+/// Filter passed to `org.bluez.Adapter1.SetDiscoveryFilter`.
+///
+/// Fields left as `None` (or an empty `uuids`) are not sent to BlueZ, which
+/// leaves them at their default.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DiscoveryFilter {
+	/// Only report devices advertising at least one of these service UUIDs.
+	pub uuids: Vec<String>,
+	/// Only report devices with a received signal strength above this value, in dBm.
+	pub rssi: Option<i16>,
+	/// Only report devices with a pathloss below this value, in dB.
+	pub pathloss: Option<u16>,
+	pub transport: Option<Transport>,
+	/// Report every advertisement instead of only the first one per device.
+	pub duplicate_data: Option<bool>,
+	/// Only report devices that are in discoverable mode.
+	pub discoverable: Option<bool>,
+	/// Only report devices whose address or name starts with this prefix.
+	pub pattern: Option<String>,
+}
+
+impl DiscoveryFilter {
+	/// LE scan for devices advertising any of `uuids`, reporting each device once.
+	///
+	/// BlueZ only applies the UUID filter to LE advertisements when the
+	/// transport is restricted to LE.
+	pub fn le_services(uuids: &[Uuid]) -> Self {
+		Self {
+			uuids: uuids.iter().map(Uuid::to_string).collect(),
+			transport: Some(Transport::Le),
+			duplicate_data: Some(false),
+			..Self::default()
+		}
+	}
+
+	/// LE scan reporting every advertisement, as needed to follow beacons
+	/// whose payload changes while the device stays the same.
+	pub fn beacons() -> Self {
+		Self {
+			transport: Some(Transport::Le),
+			duplicate_data: Some(true),
+			..Self::default()
+		}
+	}
+
+	/// Checks for combinations BlueZ rejects with an opaque error.
+	pub fn validate(&self) -> Result<(), InvalidDiscoveryFilter> {
+		if self.rssi.is_some() && self.pathloss.is_some() {
+			return Err(InvalidDiscoveryFilter(
+				"RSSI and Pathloss cannot both be set".to_owned(),
+			));
+		}
+		Ok(())
+	}
+
+	/// Serializes the filter into the `a{sv}` dictionary BlueZ expects, after
+	/// [validating](Self::validate) it.
+	pub fn to_dict(&self) -> Result<HashMap<String, Value<'static>>, InvalidDiscoveryFilter> {
+		self.validate()?;
+
+		let mut dict = HashMap::new();
+
+		if !self.uuids.is_empty() {
+			dict.insert("UUIDs".to_owned(), Value::from(self.uuids.clone()));
+		}
+		if let Some(rssi) = self.rssi {
+			dict.insert("RSSI".to_owned(), Value::from(rssi));
+		}
+		if let Some(pathloss) = self.pathloss {
+			dict.insert("Pathloss".to_owned(), Value::from(pathloss));
+		}
+		if let Some(transport) = self.transport {
+			dict.insert(
+				"Transport".to_owned(),
+				Value::from(<&'static str>::from(transport)),
+			);
+		}
+		if let Some(duplicate_data) = self.duplicate_data {
+			dict.insert("DuplicateData".to_owned(), Value::from(duplicate_data));
+		}
+		if let Some(discoverable) = self.discoverable {
+			dict.insert("Discoverable".to_owned(), Value::from(discoverable));
+		}
+		if let Some(pattern) = &self.pattern {
+			dict.insert("Pattern".to_owned(), Value::from(pattern.clone()));
+		}
+
+		Ok(dict)
+	}
+}
+
+/// A [`DiscoveryFilter`] that BlueZ would reject, with the reason.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidDiscoveryFilter(pub String);
+
+impl fmt::Display for InvalidDiscoveryFilter {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "invalid discovery filter: {}", self.0)
+	}
+}
+
+impl std::error::Error for InvalidDiscoveryFilter {}
+
+/// Stream of devices reported by BlueZ while the adapter is discovering.
+///
+/// Discovery is started when the stream is created and stopped when it is dropped,
+/// which also clears the filter.
+/// Devices that do not match the transport of the filter are left out, for
+/// controllers that report them regardless.
+pub struct DeviceDiscovery {
+	adapter: adapter1::Adapter1Proxy<'static>,
+	stream: Pin<Box<dyn Stream<Item = BluetoothDevice<'static>> + Send>>,
+}
+
+impl DeviceDiscovery {
+	pub(crate) async fn new(
+		connection: &zbus::Connection,
+		adapter_path: OwnedObjectPath,
+		filter: DiscoveryFilter,
+	) -> Result<Self, BluezError> {
+		let adapter = adapter1::Adapter1Proxy::builder(connection)
+			.path(adapter_path.clone())?
+			.build()
+			.await?;
+		let managed_object_proxy =
+			zbus::fdo::ObjectManagerProxy::new(connection, "org.bluez", "/").await?;
+
+		set_filter(&adapter, &filter).await?;
+
+		// Subscribe before starting discovery so that no device is missed.
+		let interfaces_added = managed_object_proxy.receive_interfaces_added().await?;
+		if let Err(why) = start_discovery(&adapter).await {
+			_ = clear_filter(&adapter).await;
+			return Err(why);
+		}
+
+		let transport = filter.transport.unwrap_or_default();
+
+		let connection = connection.clone();
+		let prefix = format!("{}/", adapter_path.as_str());
+		let stream = interfaces_added.filter_map(move |signal| {
+			let connection = connection.clone();
+			let prefix = prefix.clone();
+			async move {
+				let args = signal.args().ok()?;
+				let path = args.object_path();
+				let properties = args.interfaces_and_properties().get("org.bluez.Device1")?;
+				if !path.as_str().starts_with(&prefix) || !matches_transport(transport, properties) {
+					return None;
+				}
+
+				let path: OwnedObjectPath = path.to_owned().into();
+				match BluetoothDevice::new(&connection, path.clone().into()).await {
+					Ok(device) => Some(device),
+					Err(why) => {
+						tracing::warn!(?path, ?why, "failed to create discovered device");
+						None
+					}
+				}
+			}
+		});
+
+		Ok(Self {
+			adapter,
+			stream: Box::pin(stream),
+		})
+	}
+}
+
+/// Guesses the transport a device was found on from its initial properties.
+///
+/// Only BR/EDR inquiry reports a Class of Device, while random addresses and
+/// the advertising flags only exist on LE.
+fn matches_transport(transport: Transport, properties: &HashMap<&str, Value<'_>>) -> bool {
+	let is_le = properties.contains_key("AdvertisingFlags")
+		|| properties.contains_key("Appearance")
+		|| matches!(
+			properties.get("AddressType"),
+			Some(Value::Str(address_type)) if address_type.as_str() == "random"
+		);
+	let is_bredr = properties.contains_key("Class");
+
+	match transport {
+		Transport::Auto => true,
+		Transport::BrEdr => is_bredr,
+		Transport::Le => is_le || !is_bredr,
+	}
+}
+
+impl Stream for DeviceDiscovery {
+	type Item = BluetoothDevice<'static>;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		self.stream.as_mut().poll_next(cx)
+	}
+}
+
+impl Drop for DeviceDiscovery {
+	fn drop(&mut self) {
+		let Ok(handle) = tokio::runtime::Handle::try_current() else {
+			tracing::warn!("no tokio runtime available to stop discovery");
+			return;
+		};
+		let adapter = self.adapter.clone();
+		handle.spawn(async move {
+			if let Err(why) = stop_discovery(&adapter, true).await {
+				tracing::warn!(?why, "failed to stop discovery");
+			}
+		});
+	}
+}
+
+/// An ongoing discovery on an adapter, stopped when dropped.
+///
+/// A filter set for the session is cleared again when it ends, since it would
+/// otherwise keep constraining discovery started later by other components.
+///
+/// Dropping the session requires a tokio runtime; use [`DiscoverySession::stop`]
+/// to stop discovery early and observe errors.
+#[derive(Debug)]
+pub struct DiscoverySession {
+	adapter: adapter1::Adapter1Proxy<'static>,
+	filtered: bool,
+	stopped: bool,
+}
+
+impl DiscoverySession {
+	pub(crate) async fn new(
+		connection: &zbus::Connection,
+		adapter_path: OwnedObjectPath,
+		filter: Option<&DiscoveryFilter>,
+	) -> Result<Self, BluezError> {
+		let adapter = adapter1::Adapter1Proxy::builder(connection)
+			.path(adapter_path)?
+			.build()
+			.await?;
+		if let Some(filter) = filter {
+			set_filter(&adapter, filter).await?;
+		}
+
+		if let Err(why) = start_discovery(&adapter).await {
+			if filter.is_some() {
+				_ = clear_filter(&adapter).await;
+			}
+			return Err(why);
+		}
+
+		Ok(Self {
+			adapter,
+			filtered: filter.is_some(),
+			stopped: false,
+		})
+	}
+
+	/// Stops discovery and clears the filter of the session, if any.
+	pub async fn stop(mut self) -> zbus::Result<()> {
+		self.stopped = true;
+		stop_discovery(&self.adapter, self.filtered).await
+	}
+}
+
+impl Drop for DiscoverySession {
+	fn drop(&mut self) {
+		if self.stopped {
+			return;
+		}
+
+		let Ok(handle) = tokio::runtime::Handle::try_current() else {
+			tracing::warn!("no tokio runtime available to stop discovery");
+			return;
+		};
+		let adapter = self.adapter.clone();
+		let filtered = self.filtered;
+		handle.spawn(async move {
+			if let Err(why) = stop_discovery(&adapter, filtered).await {
+				tracing::warn!(?why, "failed to stop discovery");
+			}
+		});
+	}
+}
+
+/// Attempts of `StartDiscovery` before giving up on an adapter that is not ready.
+const START_ATTEMPTS: u32 = 5;
+
+/// Calls `StartDiscovery`, retrying with exponential backoff while BlueZ
+/// reports `NotReady` or `InProgress`, as it does right after power on.
+async fn start_discovery(adapter: &adapter1::Adapter1Proxy<'_>) -> Result<(), BluezError> {
+	let mut delay = Duration::from_millis(100);
+	let mut attempt = 1;
+	loop {
+		let result = adapter.start_discovery().await.map_err(BluezError::from);
+		let retry = matches!(&result, Err(BluezError::NotReady | BluezError::InProgress));
+		if !retry || attempt == START_ATTEMPTS {
+			return result;
+		}
+
+		tracing::debug!(attempt, ?delay, "adapter not ready to discover, retrying");
+		tokio::time::sleep(delay).await;
+		delay *= 2;
+		attempt += 1;
+	}
+}
+
+async fn set_filter(
+	adapter: &adapter1::Adapter1Proxy<'_>,
+	filter: &DiscoveryFilter,
+) -> Result<(), BluezError> {
+	let dict = filter.to_dict()?;
+	adapter
+		.set_discovery_filter(dict.iter().map(|(key, value)| (key.as_str(), value)).collect())
+		.await?;
+	Ok(())
+}
+
+/// Resets the discovery filter, which BlueZ does for an empty dictionary.
+async fn clear_filter(adapter: &adapter1::Adapter1Proxy<'_>) -> zbus::Result<()> {
+	adapter.set_discovery_filter(HashMap::new()).await
+}
+
+/// Calls `StopDiscovery`, then clears the filter if one was set, even if stopping failed.
+async fn stop_discovery(adapter: &adapter1::Adapter1Proxy<'_>, filtered: bool) -> zbus::Result<()> {
+	let stopped = adapter.stop_discovery().await;
+	if filtered {
+		clear_filter(adapter).await?;
+	}
+	stopped
+}
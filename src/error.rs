@@ -0,0 +1,140 @@
+//! Typed view of the `org.bluez.Error.*` D-Bus errors.
+
+use std::fmt;
+
+const PREFIX: &str = "org.bluez.Error.";
+
+/// Failure reported by BlueZ, decoded from the D-Bus error name.
+///
+/// Errors that are not BlueZ method errors are kept as [`BluezError::Zbus`].
+#[derive(Debug)]
+pub enum BluezError {
+	/// Every advertising instance the controller supports is in use.
+	AdvertisementLimit {
+		supported: u8,
+	},
+	AlreadyConnected,
+	AlreadyExists,
+	AuthenticationCanceled,
+	AuthenticationFailed,
+	AuthenticationRejected,
+	AuthenticationTimeout,
+	ConnectionAttemptFailed,
+	DoesNotExist,
+	Failed(String),
+	InProgress,
+	/// The filter was rejected before it was sent to BlueZ.
+	InvalidDiscoveryFilter(crate::discovery::InvalidDiscoveryFilter),
+	NotAuthorized,
+	NotConnected,
+	NotReady,
+	NotSupported,
+	/// A BlueZ error without a dedicated variant.
+	Other {
+		name: String,
+		message: Option<String>,
+	},
+	/// The operation did not complete within the allotted time.
+	Timeout,
+	Zbus(zbus::Error),
+}
+
+impl BluezError {
+	/// Decodes an `org.bluez.Error.*` name, returning `None` for names outside that namespace.
+	pub fn from_name(name: &str, message: Option<String>) -> Option<Self> {
+		let error = match name.strip_prefix(PREFIX)? {
+			"AlreadyConnected" => Self::AlreadyConnected,
+			"AlreadyExists" => Self::AlreadyExists,
+			"AuthenticationCanceled" => Self::AuthenticationCanceled,
+			"AuthenticationFailed" => Self::AuthenticationFailed,
+			"AuthenticationRejected" => Self::AuthenticationRejected,
+			"AuthenticationTimeout" => Self::AuthenticationTimeout,
+			"ConnectionAttemptFailed" => Self::ConnectionAttemptFailed,
+			"DoesNotExist" => Self::DoesNotExist,
+			"Failed" => Self::Failed(message.unwrap_or_default()),
+			"InProgress" => Self::InProgress,
+			"NotAuthorized" => Self::NotAuthorized,
+			"NotConnected" => Self::NotConnected,
+			"NotReady" => Self::NotReady,
+			"NotSupported" => Self::NotSupported,
+			_ => Self::Other {
+				name: name.to_owned(),
+				message,
+			},
+		};
+		Some(error)
+	}
+}
+
+impl From<zbus::Error> for BluezError {
+	fn from(error: zbus::Error) -> Self {
+		if let zbus::Error::MethodError(name, message, _) = &error {
+			if let Some(bluez_error) = Self::from_name(name.as_str(), message.clone()) {
+				return bluez_error;
+			}
+		}
+		Self::Zbus(error)
+	}
+}
+
+impl From<crate::discovery::InvalidDiscoveryFilter> for BluezError {
+	fn from(error: crate::discovery::InvalidDiscoveryFilter) -> Self {
+		Self::InvalidDiscoveryFilter(error)
+	}
+}
+
+impl fmt::Display for BluezError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::AdvertisementLimit { supported } => write!(
+				f,
+				"all {supported} advertising instances of the controller are in use"
+			),
+			Self::AlreadyConnected => f.write_str("already connected"),
+			Self::AlreadyExists => f.write_str("already exists"),
+			Self::AuthenticationCanceled => f.write_str("authentication canceled"),
+			Self::AuthenticationFailed => f.write_str("authentication failed"),
+			Self::AuthenticationRejected => f.write_str("authentication rejected"),
+			Self::AuthenticationTimeout => f.write_str("authentication timed out"),
+			Self::ConnectionAttemptFailed => f.write_str("connection attempt failed"),
+			Self::DoesNotExist => f.write_str("does not exist"),
+			Self::Failed(message) => write!(f, "failed: {message}"),
+			Self::InProgress => f.write_str("operation already in progress"),
+			Self::InvalidDiscoveryFilter(error) => error.fmt(f),
+			Self::NotAuthorized => f.write_str("not authorized"),
+			Self::NotConnected => f.write_str("not connected"),
+			Self::NotReady => f.write_str("not ready"),
+			Self::NotSupported => f.write_str("not supported"),
+			Self::Other {
+				name,
+				message: Some(message),
+			} => write!(f, "{name}: {message}"),
+			Self::Other {
+				name,
+				message: None,
+			} => f.write_str(name),
+			Self::Timeout => f.write_str("timed out"),
+			Self::Zbus(error) => error.fmt(f),
+		}
+	}
+}
+
+impl std::error::Error for BluezError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Self::InvalidDiscoveryFilter(error) => Some(error),
+			Self::Zbus(error) => Some(error),
+			_ => None,
+		}
+	}
+}
+
+/// Maps the error BlueZ returns when reading a property that is currently
+/// absent, such as `RSSI` for a device that is out of range, to `None`.
+pub(crate) fn optional_property<T>(result: zbus::Result<T>) -> zbus::Result<Option<T>> {
+	match result {
+		Ok(value) => Ok(Some(value)),
+		Err(zbus::Error::FDO(error)) if matches!(*error, zbus::fdo::Error::InvalidArgs(_)) => Ok(None),
+		Err(why) => Err(why),
+	}
+}
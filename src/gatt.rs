@@ -0,0 +1,430 @@
+//! Typed view of the GATT hierarchy BlueZ exports under a device.
+
+use std::collections::HashMap;
+use std::os::fd::OwnedFd;
+use std::os::unix::net::UnixStream;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_util::{Stream, StreamExt};
+use tokio::io::unix::AsyncFd;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, Value};
+
+use crate::{changes, error, gatt_characteristic1, gatt_descriptor1, gatt_service1, Uuid};
+
+/// UUID of the Client Characteristic Configuration descriptor.
+const CCCD_UUID: Uuid = Uuid::from_u16(0x2902);
+
+#[derive(Clone, Debug)]
+pub struct GattService<'a> {
+	pub service: gatt_service1::GattService1Proxy<'a>,
+	pub characteristics: Vec<GattCharacteristic<'a>>,
+	included: Vec<GattService<'a>>,
+}
+
+impl<'a> GattService<'a> {
+	pub fn path(&self) -> OwnedObjectPath {
+		self.service.inner().path().to_owned().into()
+	}
+
+	/// The services this service includes, from `Includes`, in the order
+	/// BlueZ lists them.
+	///
+	/// The returned services do not list includes of their own; look them up
+	/// in the resolved tree to follow nested includes.
+	pub fn includes(&self) -> Vec<GattService<'a>> {
+		self.included.clone()
+	}
+}
+
+/// How a value is written to a characteristic.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WriteType {
+	/// Write without response.
+	Command,
+	/// Write with response.
+	#[default]
+	Request,
+	/// Reliable write, which has the peripheral echo the value before it is committed.
+	Reliable,
+}
+
+impl From<WriteType> for &'static str {
+	fn from(write_type: WriteType) -> &'static str {
+		match write_type {
+			WriteType::Command => "command",
+			WriteType::Request => "request",
+			WriteType::Reliable => "reliable",
+		}
+	}
+}
+
+/// Options for [`GattCharacteristic::write`].
+#[derive(Clone, Debug, Default)]
+pub struct WriteOptions {
+	/// Offset in the characteristic value to start writing at.
+	pub offset: u16,
+	pub write_type: WriteType,
+	/// Prepare an authorized write, for characteristics requiring authorization.
+	pub prepare_authorize: bool,
+}
+
+impl WriteOptions {
+	/// Serializes the options into the `a{sv}` dictionary BlueZ expects.
+	pub fn to_dict(&self) -> HashMap<String, Value<'static>> {
+		let mut dict = HashMap::from([
+			("offset".to_owned(), Value::from(self.offset)),
+			(
+				"type".to_owned(),
+				Value::from(<&'static str>::from(self.write_type)),
+			),
+		]);
+		if self.prepare_authorize {
+			dict.insert("prepare-authorize".to_owned(), Value::from(true));
+		}
+		dict
+	}
+}
+
+#[derive(Clone, Debug)]
+pub struct GattCharacteristic<'a> {
+	pub characteristic: gatt_characteristic1::GattCharacteristic1Proxy<'a>,
+	pub descriptors: Vec<GattDescriptor<'a>>,
+}
+
+impl GattCharacteristic<'_> {
+	/// Enables notifications and streams every value the peripheral sends.
+	///
+	/// Notifications are disabled again when the stream is dropped.
+	pub async fn notifications(&self) -> zbus::Result<Notifications> {
+		let connection = self.characteristic.inner().connection();
+		let characteristic = gatt_characteristic1::GattCharacteristic1Proxy::builder(connection)
+			.path(self.path())?
+			.build()
+			.await?;
+		let properties = zbus::fdo::PropertiesProxy::builder(connection)
+			.destination("org.bluez")?
+			.path(self.path())?
+			.build()
+			.await?;
+
+		// Subscribe before enabling notifications so that no value is missed.
+		let properties_changed = properties.receive_properties_changed().await?;
+		characteristic.start_notify().await?;
+
+		let stream = properties_changed.filter_map(|signal| async move {
+			let args = signal.args().ok()?;
+			if args.interface_name().as_str() != "org.bluez.GattCharacteristic1" {
+				return None;
+			}
+			args.changed_properties()
+				.get("Value")
+				.and_then(changes::decode::<Vec<u8>>)
+		});
+
+		Ok(Notifications {
+			characteristic,
+			stream: Box::pin(stream),
+		})
+	}
+
+	/// Reads the value starting at `offset`.
+	pub async fn read(&self, offset: u16) -> zbus::Result<Vec<u8>> {
+		let offset = Value::from(offset);
+		self.characteristic
+			.read_value(HashMap::from([("offset", &offset)]))
+			.await
+	}
+
+	/// Reads the whole value, continuing at increasing offsets only while a
+	/// read returns exactly one full ATT payload of MTU - 1 bytes.
+	///
+	/// BlueZ usually performs the long read itself and returns the whole value
+	/// at once, so any other length, shorter or longer, ends the value. Without
+	/// a known MTU a single read is made.
+	pub async fn read_all(&self) -> zbus::Result<Vec<u8>> {
+		// A read response carries at most MTU - 1 bytes of the value.
+		let payload = self
+			.mtu()
+			.await?
+			.map(|mtu| usize::from(mtu.saturating_sub(1)));
+
+		let mut value = Vec::new();
+		loop {
+			let offset = u16::try_from(value.len()).map_err(|_| {
+				zbus::Error::Failure("characteristic value is longer than 65535 bytes".to_owned())
+			})?;
+			let chunk = self.read(offset).await?;
+			let full = !chunk.is_empty() && payload == Some(chunk.len());
+			value.extend_from_slice(&chunk);
+			if !full {
+				return Ok(value);
+			}
+		}
+	}
+
+	/// Writes `value`, as a write with response unless `options` says otherwise.
+	pub async fn write(&self, value: &[u8], options: WriteOptions) -> zbus::Result<()> {
+		let dict = options.to_dict();
+		self.characteristic
+			.write_value(
+				value,
+				dict.iter().map(|(key, value)| (key.as_str(), value)).collect(),
+			)
+			.await
+	}
+
+	/// Writes a `value` of up to 65535 bytes as a sequence of writes with
+	/// response, each carrying at most MTU - 3 bytes at increasing offsets.
+	///
+	/// A longer value is rejected before anything is written, and an empty one
+	/// is sent as a single zero-length write. Without a negotiated MTU, the
+	/// 23 byte minimum of ATT is assumed.
+	pub async fn write_long(&self, value: &[u8]) -> zbus::Result<()> {
+		if value.len() > usize::from(u16::MAX) {
+			return Err(zbus::Error::Failure(
+				"characteristic value is longer than 65535 bytes".to_owned(),
+			));
+		}
+		if value.is_empty() {
+			return self.write(value, WriteOptions::default()).await;
+		}
+
+		let mtu = self.mtu().await?.unwrap_or(23);
+		let chunk_len = usize::from(mtu.saturating_sub(3)).max(1);
+
+		for (index, chunk) in value.chunks(chunk_len).enumerate() {
+			let options = WriteOptions {
+				// Below 65535, since the length was checked above.
+				offset: (index * chunk_len) as u16,
+				..WriteOptions::default()
+			};
+			self.write(chunk, options).await?;
+		}
+
+		Ok(())
+	}
+
+	/// The ATT MTU negotiated for the connection, or `None` if BlueZ does not report it.
+	pub async fn mtu(&self) -> zbus::Result<Option<u16>> {
+		error::optional_property(self.characteristic.mtu().await)
+	}
+
+	/// Enables or disables notifications and indications by writing the Client
+	/// Characteristic Configuration descriptor directly.
+	///
+	/// This is meant for peripherals that do not work with `StartNotify`. Note
+	/// that BlueZ versions which manage the descriptor themselves reject the
+	/// write with `org.bluez.Error.NotPermitted`.
+	pub async fn set_cccd(&self, notify: bool, indicate: bool) -> zbus::Result<()> {
+		let mut cccd = None;
+		for descriptor in &self.descriptors {
+			if descriptor.descriptor.uuid().await?.parse::<Uuid>().ok() == Some(CCCD_UUID) {
+				cccd = Some(descriptor);
+				break;
+			}
+		}
+		let cccd = cccd.ok_or_else(|| {
+			zbus::Error::Failure(
+				"characteristic has no Client Characteristic Configuration descriptor".to_owned(),
+			)
+		})?;
+
+		let value = u8::from(notify) | u8::from(indicate) << 1;
+		cccd.write(&[value, 0x00], 0).await
+	}
+
+	/// Acquires a socket which receives every notification of the characteristic,
+	/// along with the negotiated MTU.
+	///
+	/// Notifications stop when the socket is closed.
+	pub async fn acquire_notify(&self) -> zbus::Result<(AsyncFd<OwnedFd>, u16)> {
+		let (fd, mtu) = self.characteristic.acquire_notify(HashMap::new()).await?;
+		Ok((async_fd(fd)?, mtu))
+	}
+
+	/// Acquires a socket to write values without response, along with the
+	/// negotiated MTU.
+	pub async fn acquire_write(&self) -> zbus::Result<(AsyncFd<OwnedFd>, u16)> {
+		let (fd, mtu) = self.characteristic.acquire_write(HashMap::new()).await?;
+		Ok((async_fd(fd)?, mtu))
+	}
+
+	pub fn path(&self) -> OwnedObjectPath {
+		self.characteristic.inner().path().to_owned().into()
+	}
+}
+
+/// Stream of values notified by a characteristic.
+///
+/// `StopNotify` is called when the stream is dropped.
+pub struct Notifications {
+	characteristic: gatt_characteristic1::GattCharacteristic1Proxy<'static>,
+	stream: Pin<Box<dyn Stream<Item = Vec<u8>> + Send>>,
+}
+
+impl Stream for Notifications {
+	type Item = Vec<u8>;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		self.stream.as_mut().poll_next(cx)
+	}
+}
+
+impl Drop for Notifications {
+	fn drop(&mut self) {
+		let Ok(handle) = tokio::runtime::Handle::try_current() else {
+			tracing::warn!("no tokio runtime available to stop notifications");
+			return;
+		};
+		let characteristic = self.characteristic.clone();
+		handle.spawn(async move {
+			if let Err(why) = characteristic.stop_notify().await {
+				tracing::warn!(?why, "failed to stop notifications");
+			}
+		});
+	}
+}
+
+#[derive(Clone, Debug)]
+pub struct GattDescriptor<'a> {
+	pub descriptor: gatt_descriptor1::GattDescriptor1Proxy<'a>,
+}
+
+impl GattDescriptor<'_> {
+	/// Reads the value starting at `offset`.
+	pub async fn read(&self, offset: u16) -> zbus::Result<Vec<u8>> {
+		let offset = Value::from(offset);
+		self.descriptor
+			.read_value(HashMap::from([("offset", &offset)]))
+			.await
+	}
+
+	/// Writes `value`, starting at `offset`.
+	pub async fn write(&self, value: &[u8], offset: u16) -> zbus::Result<()> {
+		let offset = Value::from(offset);
+		self.descriptor
+			.write_value(value, HashMap::from([("offset", &offset)]))
+			.await
+	}
+
+	pub fn path(&self) -> OwnedObjectPath {
+		self.descriptor.inner().path().to_owned().into()
+	}
+}
+
+/// Resolves the GATT services of the device at `device_path`.
+///
+/// BlueZ exports the hierarchy as `<device>/serviceXXXX/charYYYY/descZZZZ`, so
+/// every object is attached to the object whose path is its direct parent.
+/// Services, characteristics and descriptors are ordered by object path,
+/// which follows the attribute handles.
+pub async fn gatt_services<'a>(
+	connection: &zbus::Connection,
+	device_path: &ObjectPath<'_>,
+) -> zbus::Result<Vec<GattService<'a>>> {
+	let managed_object_proxy =
+		zbus::fdo::ObjectManagerProxy::new(connection, "org.bluez", "/").await?;
+	let managed_object: zbus::fdo::ManagedObjects =
+		managed_object_proxy.get_managed_objects().await?;
+
+	let prefix = format!("{}/", device_path.as_str());
+	let mut service_paths = Vec::new();
+	let mut characteristic_paths = Vec::new();
+	let mut descriptor_paths = Vec::new();
+	for (path, interfaces) in managed_object {
+		if !path.as_str().starts_with(&prefix) {
+			continue;
+		}
+		if interfaces.contains_key("org.bluez.GattService1") {
+			service_paths.push(path);
+		} else if interfaces.contains_key("org.bluez.GattCharacteristic1") {
+			characteristic_paths.push(path);
+		} else if interfaces.contains_key("org.bluez.GattDescriptor1") {
+			descriptor_paths.push(path);
+		}
+	}
+	service_paths.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+	characteristic_paths.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+	descriptor_paths.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+	let mut services = Vec::with_capacity(service_paths.len());
+	let mut included_paths = Vec::with_capacity(service_paths.len());
+	for service_path in service_paths {
+		let mut characteristics = Vec::new();
+		for characteristic_path in characteristic_paths
+			.iter()
+			.filter(|path| parent(path) == service_path.as_str())
+		{
+			let mut descriptors = Vec::new();
+			for descriptor_path in descriptor_paths
+				.iter()
+				.filter(|path| parent(path) == characteristic_path.as_str())
+			{
+				descriptors.push(GattDescriptor {
+					descriptor: gatt_descriptor1::GattDescriptor1Proxy::builder(connection)
+						.path(descriptor_path.clone())?
+						.build()
+						.await?,
+				});
+			}
+
+			characteristics.push(GattCharacteristic {
+				characteristic: gatt_characteristic1::GattCharacteristic1Proxy::builder(
+					connection,
+				)
+				.path(characteristic_path.clone())?
+				.build()
+				.await?,
+				descriptors,
+			});
+		}
+
+		let service = gatt_service1::GattService1Proxy::builder(connection)
+			.path(service_path)?
+			.build()
+			.await?;
+		included_paths.push(error::optional_property(service.includes().await)?.unwrap_or_default());
+		services.push(GattService {
+			service,
+			characteristics,
+			included: Vec::new(),
+		});
+	}
+
+	// Included services are exported as services of the device themselves,
+	// so they are attached once every service has been resolved.
+	let included: Vec<Vec<GattService<'a>>> = included_paths
+		.iter()
+		.map(|paths| {
+			paths
+				.iter()
+				.filter_map(|path| {
+					services
+						.iter()
+						.find(|service| service.service.inner().path().as_str() == path.as_str())
+						.cloned()
+				})
+				.collect()
+		})
+		.collect();
+	for (service, included) in services.iter_mut().zip(included) {
+		service.included = included;
+	}
+
+	Ok(services)
+}
+
+fn async_fd(fd: zbus::zvariant::OwnedFd) -> std::io::Result<AsyncFd<OwnedFd>> {
+	// The socket is a SOCK_SEQPACKET, but `UnixStream` is the only std type that
+	// can toggle `O_NONBLOCK` on an arbitrary socket as required by `AsyncFd`.
+	let socket = UnixStream::from(OwnedFd::from(fd));
+	socket.set_nonblocking(true)?;
+	AsyncFd::new(OwnedFd::from(socket))
+}
+
+fn parent(path: &OwnedObjectPath) -> &str {
+	path.as_str()
+		.rsplit_once('/')
+		.map_or("", |(parent, _)| parent)
+}
@@ -0,0 +1,495 @@
+//! Integrations for exposing local GATT services, acting as a peripheral.
+//!
+//! An application is a tree of objects exported under a single
+//! `org.freedesktop.DBus.ObjectManager`, which BlueZ walks when it is
+//! registered with `org.bluez.GattManager1`:
+//!
+//! ```text
+//! <application>
+//! <application>/service0
+//! <application>/service0/char0
+//! ```
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use tokio::sync::{mpsc, oneshot};
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue};
+
+use crate::{changes, gatt_manager1};
+
+/// A local GATT service.
+#[derive(Clone, Debug, Default)]
+pub struct Service {
+    pub uuid: String,
+    pub primary: bool,
+    pub characteristics: Vec<Characteristic>,
+}
+
+/// A characteristic of a local GATT service.
+#[derive(Clone, Debug, Default)]
+pub struct Characteristic {
+    pub uuid: String,
+    /// Supported operations, such as `"read"`, `"write"` or `"notify"`.
+    pub flags: Vec<String>,
+    /// Initial value, reported through the `Value` property.
+    pub value: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub enum Message {
+    ReadValue {
+        characteristic: OwnedObjectPath,
+        uuid: String,
+        /// The remote device reading the value, when BlueZ reports it.
+        device: Option<OwnedObjectPath>,
+        offset: u16,
+        response: ReadValueResponder,
+    },
+    StartNotify {
+        characteristic: OwnedObjectPath,
+        uuid: String,
+    },
+    StopNotify {
+        characteristic: OwnedObjectPath,
+        uuid: String,
+    },
+    WriteValue {
+        characteristic: OwnedObjectPath,
+        uuid: String,
+        /// The remote device writing the value, when BlueZ reports it.
+        device: Option<OwnedObjectPath>,
+        offset: u16,
+        value: Vec<u8>,
+        response: WriteValueResponder,
+    },
+}
+
+/// Errors returned to BlueZ, in the `org.bluez.Error` namespace.
+#[derive(Debug, zbus::DBusError)]
+#[zbus(prefix = "org.bluez.Error")]
+pub enum GattError {
+    #[zbus(error)]
+    ZBus(zbus::Error),
+    /// The request was dropped without a response.
+    Failed(String),
+    /// The request was rejected by the application.
+    NotPermitted(String),
+}
+
+/// Reply to a `ReadValue` request.
+#[derive(Debug)]
+pub struct ReadValueResponder(oneshot::Sender<Option<Vec<u8>>>);
+
+impl ReadValueResponder {
+    /// Replies with `value`, starting at the requested offset.
+    pub fn accept(self, value: Vec<u8>) {
+        _ = self.0.send(Some(value));
+    }
+
+    /// Replies with `org.bluez.Error.NotPermitted`.
+    pub fn reject(self) {
+        _ = self.0.send(None);
+    }
+}
+
+/// Reply to a `WriteValue` request.
+#[derive(Debug)]
+pub struct WriteValueResponder(oneshot::Sender<Option<()>>);
+
+impl WriteValueResponder {
+    pub fn accept(self) {
+        _ = self.0.send(Some(()));
+    }
+
+    /// Replies with `org.bluez.Error.NotPermitted`.
+    pub fn reject(self) {
+        _ = self.0.send(None);
+    }
+}
+
+/// Builds the object tree of a GATT application.
+#[derive(Debug)]
+pub struct ApplicationBuilder {
+    path: OwnedObjectPath,
+    services: Vec<Service>,
+}
+
+impl ApplicationBuilder {
+    /// Starts an application rooted at `path`, such as `/org/example/gatt`.
+    pub fn new(path: OwnedObjectPath) -> Self {
+        Self {
+            path,
+            services: Vec::new(),
+        }
+    }
+
+    pub fn service(mut self, service: Service) -> Self {
+        self.services.push(service);
+        self
+    }
+
+    /// Exports the application on `connection` and registers it with the
+    /// adapter at `adapter_path`.
+    ///
+    /// Requests for every characteristic are sent to the returned receiver.
+    pub async fn register(
+        self,
+        connection: &zbus::Connection,
+        adapter_path: &ObjectPath<'_>,
+    ) -> zbus::Result<(Application, mpsc::Receiver<Message>)> {
+        let (message_sender, message_receiver) = tokio::sync::mpsc::channel(1);
+
+        let mut application = Application {
+            connection: connection.clone(),
+            path: self.path,
+            adapter_path: adapter_path.to_owned().into(),
+            service_paths: Vec::new(),
+            characteristic_paths: Vec::new(),
+        };
+
+        // Checked before exporting anything else, so that the cleanup below
+        // never removes an object manager this application does not own.
+        crate::serve_at(
+            connection,
+            application.path.as_str(),
+            zbus::fdo::ObjectManager,
+        )
+        .await?;
+
+        if let Err(why) = application.export(self.services, message_sender).await {
+            application.remove_objects().await;
+            return Err(why);
+        }
+
+        let registered = async {
+            let manager = gatt_manager1::GattManager1Proxy::builder(connection)
+                .path(adapter_path)?
+                .build()
+                .await?;
+            manager
+                .register_application(&application.path, HashMap::new())
+                .await
+        };
+
+        if let Err(why) = registered.await {
+            application.remove_objects().await;
+            return Err(why);
+        }
+
+        Ok((application, message_receiver))
+    }
+}
+
+/// A GATT application exported on the bus and registered with BlueZ.
+#[derive(Debug)]
+pub struct Application {
+    connection: zbus::Connection,
+    path: OwnedObjectPath,
+    adapter_path: OwnedObjectPath,
+    service_paths: Vec<OwnedObjectPath>,
+    characteristic_paths: Vec<OwnedObjectPath>,
+}
+
+impl Application {
+    pub fn path(&self) -> OwnedObjectPath {
+        self.path.clone()
+    }
+
+    /// Paths of the exported characteristics, ordered by service and then
+    /// by the order they were added in.
+    pub fn characteristic_paths(&self) -> &[OwnedObjectPath] {
+        &self.characteristic_paths
+    }
+
+    /// Updates the value of the characteristic at `characteristic`, notifying
+    /// the devices that enabled notifications.
+    pub async fn notify(
+        &self,
+        characteristic: &ObjectPath<'_>,
+        value: Vec<u8>,
+    ) -> zbus::Result<()> {
+        let interface = self
+            .connection
+            .object_server()
+            .interface::<_, CharacteristicInterface>(characteristic)
+            .await?;
+
+        // The interface is only borrowed immutably, so a notification can be
+        // sent while a read or write request is awaiting its response.
+        let characteristic = interface.get().await;
+        *characteristic.value.lock().unwrap() = value;
+        characteristic
+            .value_changed(interface.signal_context())
+            .await
+    }
+
+    /// Unregisters the application from BlueZ and removes its objects from the bus.
+    pub async fn unregister(self) -> zbus::Result<()> {
+        let unregistered = async {
+            let manager = gatt_manager1::GattManager1Proxy::builder(&self.connection)
+                .path(&self.adapter_path)?
+                .build()
+                .await?;
+            manager.unregister_application(&self.path).await
+        };
+
+        let result = unregistered.await;
+        self.remove_objects().await;
+        result
+    }
+
+    async fn export(
+        &mut self,
+        services: Vec<Service>,
+        message_sender: mpsc::Sender<Message>,
+    ) -> zbus::Result<()> {
+        for (service_index, service) in services.into_iter().enumerate() {
+            let service_path = OwnedObjectPath::try_from(format!(
+                "{}/service{service_index}",
+                self.path.as_str()
+            ))?;
+            crate::serve_at(
+                &self.connection,
+                service_path.as_str(),
+                ServiceInterface {
+                    uuid: service.uuid,
+                    primary: service.primary,
+                },
+            )
+            .await?;
+            self.service_paths.push(service_path.clone());
+
+            for (characteristic_index, characteristic) in
+                service.characteristics.into_iter().enumerate()
+            {
+                let characteristic_path = OwnedObjectPath::try_from(format!(
+                    "{}/char{characteristic_index}",
+                    service_path.as_str()
+                ))?;
+                crate::serve_at(
+                    &self.connection,
+                    characteristic_path.as_str(),
+                    CharacteristicInterface {
+                        path: characteristic_path.clone(),
+                        service: service_path.clone(),
+                        uuid: characteristic.uuid,
+                        flags: characteristic.flags,
+                        value: Mutex::new(characteristic.value),
+                        notifying: AtomicBool::new(false),
+                        message_sender: message_sender.clone(),
+                    },
+                )
+                .await?;
+                self.characteristic_paths.push(characteristic_path);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn remove_objects(&self) {
+        let object_server = self.connection.object_server();
+        for path in &self.characteristic_paths {
+            _ = object_server
+                .remove::<CharacteristicInterface, _>(path.as_str())
+                .await;
+        }
+        for path in &self.service_paths {
+            _ = object_server
+                .remove::<ServiceInterface, _>(path.as_str())
+                .await;
+        }
+        _ = object_server
+            .remove::<zbus::fdo::ObjectManager, _>(self.path.as_str())
+            .await;
+    }
+}
+
+struct ServiceInterface {
+    uuid: String,
+    primary: bool,
+}
+
+#[zbus::interface(name = "org.bluez.GattService1")]
+impl ServiceInterface {
+    /// 128-bit service UUID.
+    #[zbus(property, name = "UUID")]
+    fn uuid(&self) -> String {
+        self.uuid.clone()
+    }
+
+    /// Indicates whether or not this GATT service is a
+    /// primary service. If false, the service is secondary.
+    #[zbus(property)]
+    fn primary(&self) -> bool {
+        self.primary
+    }
+}
+
+struct CharacteristicInterface {
+    path: OwnedObjectPath,
+    service: OwnedObjectPath,
+    uuid: String,
+    flags: Vec<String>,
+    value: Mutex<Vec<u8>>,
+    notifying: AtomicBool,
+    message_sender: mpsc::Sender<Message>,
+}
+
+#[zbus::interface(name = "org.bluez.GattCharacteristic1")]
+impl CharacteristicInterface {
+    /// Issues a request to read the value of the
+    /// characteristic and returns the value if the
+    /// operation was successful.
+    async fn read_value(&self, options: HashMap<String, OwnedValue>) -> Result<Vec<u8>, GattError> {
+        let (device, offset) = request_options(&options);
+        tracing::debug!(path = ?self.path, ?device, offset, "read_value");
+
+        let (response, response_rx) = oneshot::channel();
+
+        _ = self
+            .message_sender
+            .send(Message::ReadValue {
+                characteristic: self.path.clone(),
+                uuid: self.uuid.clone(),
+                device,
+                offset,
+                response: ReadValueResponder(response),
+            })
+            .await;
+
+        await_reply(response_rx).await
+    }
+
+    /// Issues a request to write the value of the
+    /// characteristic.
+    async fn write_value(
+        &self,
+        value: Vec<u8>,
+        options: HashMap<String, OwnedValue>,
+    ) -> Result<(), GattError> {
+        let (device, offset) = request_options(&options);
+        tracing::debug!(path = ?self.path, ?device, offset, "write_value");
+
+        let (response, response_rx) = oneshot::channel();
+
+        _ = self
+            .message_sender
+            .send(Message::WriteValue {
+                characteristic: self.path.clone(),
+                uuid: self.uuid.clone(),
+                device,
+                offset,
+                value,
+                response: WriteValueResponder(response),
+            })
+            .await;
+
+        await_reply(response_rx).await
+    }
+
+    /// Starts a notification session from this characteristic
+    /// if it supports value notifications or indications.
+    async fn start_notify(
+        &self,
+        #[zbus(signal_context)] context: zbus::SignalContext<'_>,
+    ) -> zbus::fdo::Result<()> {
+        tracing::debug!(path = ?self.path, "start_notify");
+
+        if !self.notifying.swap(true, Ordering::SeqCst) {
+            self.notifying_changed(&context).await?;
+            _ = self
+                .message_sender
+                .send(Message::StartNotify {
+                    characteristic: self.path.clone(),
+                    uuid: self.uuid.clone(),
+                })
+                .await;
+        }
+
+        Ok(())
+    }
+
+    /// Cancel any previous StartNotify transaction.
+    async fn stop_notify(
+        &self,
+        #[zbus(signal_context)] context: zbus::SignalContext<'_>,
+    ) -> zbus::fdo::Result<()> {
+        tracing::debug!(path = ?self.path, "stop_notify");
+
+        if self.notifying.swap(false, Ordering::SeqCst) {
+            self.notifying_changed(&context).await?;
+            _ = self
+                .message_sender
+                .send(Message::StopNotify {
+                    characteristic: self.path.clone(),
+                    uuid: self.uuid.clone(),
+                })
+                .await;
+        }
+
+        Ok(())
+    }
+
+    /// 128-bit characteristic UUID.
+    #[zbus(property, name = "UUID")]
+    fn uuid(&self) -> String {
+        self.uuid.clone()
+    }
+
+    /// Object path of the GATT service the characteristic
+    /// belongs to.
+    #[zbus(property)]
+    fn service(&self) -> OwnedObjectPath {
+        self.service.clone()
+    }
+
+    /// The cached value of the characteristic. This property
+    /// gets updated only after a successful read request and
+    /// when a notification or indication is received.
+    #[zbus(property)]
+    fn value(&self) -> Vec<u8> {
+        self.value.lock().unwrap().clone()
+    }
+
+    /// True, if notifications or indications on this
+    /// characteristic are currently enabled.
+    #[zbus(property)]
+    fn notifying(&self) -> bool {
+        self.notifying.load(Ordering::SeqCst)
+    }
+
+    /// Defines how the characteristic value can be used.
+    #[zbus(property)]
+    fn flags(&self) -> Vec<String> {
+        self.flags.clone()
+    }
+}
+
+/// Extracts the remote device and offset from the options of a read or write request.
+fn request_options(options: &HashMap<String, OwnedValue>) -> (Option<OwnedObjectPath>, u16) {
+    let device = options
+        .get("device")
+        .and_then(|device| changes::decode::<OwnedObjectPath>(device));
+    let offset = options
+        .get("offset")
+        .and_then(|offset| changes::decode::<u16>(offset))
+        .unwrap_or(0);
+    (device, offset)
+}
+
+/// Waits for a responder to be used, mapping a rejection or a dropped
+/// responder to the matching BlueZ error.
+async fn await_reply<T>(response_rx: oneshot::Receiver<Option<T>>) -> Result<T, GattError> {
+    match response_rx.await {
+        Ok(Some(value)) => Ok(value),
+        Ok(None) => Err(GattError::NotPermitted(
+            "rejected by application".to_owned(),
+        )),
+        Err(_) => Err(GattError::Failed(
+            "request dropped by application".to_owned(),
+        )),
+    }
+}
@@ -0,0 +1,43 @@
+//! # D-Bus interface proxy for: `org.bluez.GattService1`
+//!
+//! This code was generated by `zbus-xmlgen` `4.1.0` from D-Bus introspection data.
+//! Source: `Interface '/org/bluez/hci0/dev_14_3F_A6_A8_16_68/service0001' from service 'org.bluez' on system bus`.
+//!
+//! You may prefer to adapt it, instead of using it verbatim.
+//!
+//! More information can be found in the [Writing a client proxy] section of the zbus
+//! documentation.
+//!
+//! This type implements the [D-Bus standard interfaces], (`org.freedesktop.DBus.*`) for which the
+//! following zbus API can be used:
+//!
+//! * [`zbus::fdo::IntrospectableProxy`]
+//! * [`zbus::fdo::PropertiesProxy`]
+//!
+//! Consequently `zbus-xmlgen` did not generate code for the above interfaces.
+//!
+//! [Writing a client proxy]: https://dbus2.github.io/zbus/client.html
+//! [D-Bus standard interfaces]: https://dbus.freedesktop.org/doc/dbus-specification.html#standard-interfaces,
+use zbus::proxy;
+#[proxy(interface = "org.bluez.GattService1", default_service = "org.bluez")]
+trait GattService1 {
+	/// Device property
+	#[zbus(property)]
+	fn device(&self) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+
+	/// Handle property
+	#[zbus(property)]
+	fn handle(&self) -> zbus::Result<u16>;
+
+	/// Includes property
+	#[zbus(property)]
+	fn includes(&self) -> zbus::Result<Vec<zbus::zvariant::OwnedObjectPath>>;
+
+	/// Primary property
+	#[zbus(property)]
+	fn primary(&self) -> zbus::Result<bool>;
+
+	/// UUID property
+	#[zbus(property, name = "UUID")]
+	fn uuid(&self) -> zbus::Result<String>;
+}
@@ -0,0 +1,64 @@
+//! # D-Bus interface proxy for: `org.bluez.Input1`
+//!
+//! This code was generated by `zbus-xmlgen` `4.1.0` from D-Bus introspection data.
+//! Source: `Interface '/org/bluez/hci0/dev_14_3F_A6_A8_16_68' from service 'org.bluez' on system bus`.
+//!
+//! You may prefer to adapt it, instead of using it verbatim.
+//!
+//! More information can be found in the [Writing a client proxy] section of the zbus
+//! documentation.
+//!
+//! This type implements the [D-Bus standard interfaces], (`org.freedesktop.DBus.*`) for which the
+//! following zbus API can be used:
+//!
+//! * [`zbus::fdo::IntrospectableProxy`]
+//! * [`zbus::fdo::PropertiesProxy`]
+//!
+//! Consequently `zbus-xmlgen` did not generate code for the above interfaces.
+//!
+//! [Writing a client proxy]: https://dbus2.github.io/zbus/client.html
+//! [D-Bus standard interfaces]: https://dbus.freedesktop.org/doc/dbus-specification.html#standard-interfaces,
+use zbus::proxy;
+#[proxy(interface = "org.bluez.Input1", default_service = "org.bluez")]
+trait Input1 {
+	/// ReconnectMode property
+	#[zbus(property)]
+	fn reconnect_mode(&self) -> zbus::Result<ReconnectMode>;
+}
+
+/// Which side of the link reconnects the HID device after it disconnects.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReconnectMode {
+	/// Neither side reconnects.
+	None,
+	/// Only the host initiates reconnections.
+	HostOnly,
+	/// Only the device initiates reconnections.
+	DeviceOnly,
+	/// Either side may reconnect.
+	Any,
+}
+
+impl TryFrom<&str> for ReconnectMode {
+	type Error = zbus::zvariant::Error;
+
+	fn try_from(mode: &str) -> Result<Self, Self::Error> {
+		match mode {
+			"none" => Ok(ReconnectMode::None),
+			"host" => Ok(ReconnectMode::HostOnly),
+			"device" => Ok(ReconnectMode::DeviceOnly),
+			"any" => Ok(ReconnectMode::Any),
+			_ => Err(zbus::zvariant::Error::Message(format!(
+				"unknown reconnect mode: {mode}"
+			))),
+		}
+	}
+}
+
+impl TryFrom<zbus::zvariant::OwnedValue> for ReconnectMode {
+	type Error = zbus::zvariant::Error;
+
+	fn try_from(value: zbus::zvariant::OwnedValue) -> Result<Self, Self::Error> {
+		ReconnectMode::try_from(String::try_from(value)?.as_str())
+	}
+}
@@ -0,0 +1,233 @@
+//! Integrations for creating bluez LE advertisements.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use zbus::zvariant::Value;
+
+use crate::Uuid;
+
+pub fn create(params: Params) -> (Advertisement, mpsc::Receiver<Message>) {
+    let (message_sender, message_receiver) = tokio::sync::mpsc::channel(1);
+
+    (
+        Advertisement {
+            params,
+            message_sender,
+        },
+        message_receiver,
+    )
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub enum AdvertisementType {
+    Broadcast,
+    #[default]
+    Peripheral,
+}
+
+impl From<AdvertisementType> for &'static str {
+    fn from(advertisement_type: AdvertisementType) -> &'static str {
+        match advertisement_type {
+            AdvertisementType::Broadcast => "broadcast",
+            AdvertisementType::Peripheral => "peripheral",
+        }
+    }
+}
+
+/// Contents of the advertisement, exposed to BlueZ as properties.
+#[derive(Clone, Debug, Default)]
+pub struct Params {
+    pub advertisement_type: AdvertisementType,
+    pub service_uuids: Vec<String>,
+    pub solicit_uuids: Vec<String>,
+    /// Manufacturer specific data, keyed by company identifier.
+    pub manufacturer_data: HashMap<u16, Vec<u8>>,
+    /// Service data, keyed by service UUID.
+    pub service_data: HashMap<Uuid, Vec<u8>>,
+    pub local_name: String,
+    /// Values BlueZ should add to the advertisement itself, such as
+    /// `"tx-power"`, `"appearance"` or `"local-name"`.
+    pub includes: Vec<String>,
+    pub discoverable: bool,
+    /// GAP appearance to advertise, see [`crate::appearance::Appearance`].
+    pub appearance: Option<u16>,
+    /// How long the advertisement is kept on air before rotating to the next
+    /// one, when several are registered. BlueZ defaults to 2 seconds.
+    pub duration: Option<Duration>,
+    /// How long until the advertisement is removed. `None` keeps it until it
+    /// is unregistered.
+    pub timeout: Option<Duration>,
+    /// Advertising interval range. When only one bound is set, the other one
+    /// is set to the same value; when neither is, the kernel default of
+    /// 1.28 seconds (`0x0800` slots) is used for both.
+    pub min_interval: Option<Duration>,
+    pub max_interval: Option<Duration>,
+}
+
+impl Params {
+    /// Adds manufacturer specific data for the company identifier `company`.
+    pub fn with_manufacturer_data(mut self, company: u16, data: impl Into<Vec<u8>>) -> Self {
+        self.manufacturer_data.insert(company, data.into());
+        self
+    }
+
+    /// Adds service data for the service `uuid`.
+    pub fn with_service_data(mut self, uuid: Uuid, data: impl Into<Vec<u8>>) -> Self {
+        self.service_data.insert(uuid, data.into());
+        self
+    }
+}
+
+#[derive(Debug)]
+pub enum Message {
+    Release,
+}
+
+pub struct Advertisement {
+    params: Params,
+    pub(self) message_sender: mpsc::Sender<Message>,
+}
+
+#[zbus::interface(name = "org.bluez.LEAdvertisement1")]
+impl Advertisement {
+    /// This method gets called when the service daemon
+    /// removes the Advertisement. A client can use it to do
+    /// cleanup tasks. There is no need to call
+    /// UnregisterAdvertisement because when this method gets
+    /// called it has already been unregistered.
+    async fn release(&mut self) -> zbus::fdo::Result<()> {
+        tracing::debug!("release");
+
+        _ = self.message_sender.send(Message::Release).await;
+
+        Ok(())
+    }
+
+    /// Determines the type of advertising packet requested.
+    #[zbus(property, name = "Type")]
+    fn advertisement_type(&self) -> String {
+        <&'static str>::from(self.params.advertisement_type).to_owned()
+    }
+
+    /// List of UUIDs to include in the "Service UUID" field of
+    /// the Advertising Data.
+    #[zbus(property, name = "ServiceUUIDs")]
+    fn service_uuids(&self) -> Vec<String> {
+        self.params.service_uuids.clone()
+    }
+
+    /// Array of UUIDs to include in "Service Solicitation"
+    /// Advertisement Data.
+    #[zbus(property, name = "SolicitUUIDs")]
+    fn solicit_uuids(&self) -> Vec<String> {
+        self.params.solicit_uuids.clone()
+    }
+
+    /// Manufacturer Data fields to include in the Advertising Data.
+    /// Keys are the Manufacturer ID to associate with the data.
+    #[zbus(property)]
+    fn manufacturer_data(&self) -> HashMap<u16, Value<'static>> {
+        self.params
+            .manufacturer_data
+            .iter()
+            .map(|(company, data)| (*company, Value::from(data.clone())))
+            .collect()
+    }
+
+    /// Service Data elements to include. The keys are the
+    /// UUID to associate with the data.
+    #[zbus(property)]
+    fn service_data(&self) -> HashMap<String, Value<'static>> {
+        self.params
+            .service_data
+            .iter()
+            .map(|(uuid, data)| (uuid.to_string(), Value::from(data.clone())))
+            .collect()
+    }
+
+    /// Local name to be used in the advertising report. If the
+    /// string is too big to fit into the packet it will be
+    /// truncated.
+    #[zbus(property)]
+    fn local_name(&self) -> String {
+        self.params.local_name.clone()
+    }
+
+    /// List of features to be included in the advertising
+    /// packet.
+    #[zbus(property)]
+    fn includes(&self) -> Vec<String> {
+        self.params.includes.clone()
+    }
+
+    /// Advertise as general discoverable. When present this
+    /// will override adapter Discoverable property.
+    #[zbus(property)]
+    fn discoverable(&self) -> bool {
+        self.params.discoverable
+    }
+
+    /// Appearance to be used in the advertising report.
+    #[zbus(property)]
+    fn appearance(&self) -> u16 {
+        // BlueZ leaves the appearance out of the advertisement for 0xFFFF.
+        self.params.appearance.unwrap_or(u16::MAX)
+    }
+
+    /// Rotation duration of the advertisement in seconds. If
+    /// there are other applications advertising no duration is
+    /// set the default is 2 seconds.
+    #[zbus(property)]
+    fn duration(&self) -> u16 {
+        self.params
+            .duration
+            .map_or(2, |duration| secs(duration).max(1))
+    }
+
+    /// Timeout of the advertisement in seconds. This defines
+    /// the lifetime of the advertisement.
+    #[zbus(property)]
+    fn timeout(&self) -> u16 {
+        self.params
+            .timeout
+            .map_or(0, |timeout| secs(timeout).max(1))
+    }
+
+    /// Minimum advertising interval to be used by the
+    /// advertising set, in milliseconds.
+    #[zbus(property)]
+    fn min_interval(&self) -> u32 {
+        millis(
+            self.params
+                .min_interval
+                .or(self.params.max_interval)
+                .unwrap_or(DEFAULT_INTERVAL),
+        )
+    }
+
+    /// Maximum advertising interval to be used by the
+    /// advertising set, in milliseconds.
+    #[zbus(property)]
+    fn max_interval(&self) -> u32 {
+        millis(
+            self.params
+                .max_interval
+                .or(self.params.min_interval)
+                .unwrap_or(DEFAULT_INTERVAL),
+        )
+    }
+}
+
+/// The advertising interval the kernel uses when none is requested. BlueZ
+/// rejects an interval of 0, so this is exported instead of leaving it unset.
+const DEFAULT_INTERVAL: Duration = Duration::from_millis(1280);
+
+fn secs(duration: Duration) -> u16 {
+    u16::try_from(duration.as_secs()).unwrap_or(u16::MAX)
+}
+
+fn millis(duration: Duration) -> u32 {
+    u32::try_from(duration.as_millis()).unwrap_or(u32::MAX)
+}
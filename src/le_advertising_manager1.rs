@@ -0,0 +1,174 @@
+//! # D-Bus interface proxy for: `org.bluez.LEAdvertisingManager1`
+//!
+//! This code was generated by `zbus-xmlgen` `4.1.0` from D-Bus introspection data.
+//! Source: `Interface '/org/bluez/hci0' from service 'org.bluez' on system bus`.
+//!
+//! You may prefer to adapt it, instead of using it verbatim.
+//!
+//! More information can be found in the [Writing a client proxy] section of the zbus
+//! documentation.
+//!
+//! This type implements the [D-Bus standard interfaces], (`org.freedesktop.DBus.*`) for which the
+//! following zbus API can be used:
+//!
+//! * [`zbus::fdo::IntrospectableProxy`]
+//! * [`zbus::fdo::PropertiesProxy`]
+//!
+//! Consequently `zbus-xmlgen` did not generate code for the above interfaces.
+//!
+//! [Writing a client proxy]: https://dbus2.github.io/zbus/client.html
+//! [D-Bus standard interfaces]: https://dbus.freedesktop.org/doc/dbus-specification.html#standard-interfaces,
+use zbus::proxy;
+#[proxy(interface = "org.bluez.LEAdvertisingManager1", default_service = "org.bluez")]
+trait LEAdvertisingManager1 {
+	/// RegisterAdvertisement method
+	fn register_advertisement(
+		&self,
+		advertisement: &zbus::zvariant::ObjectPath<'_>,
+		options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+	) -> zbus::Result<()>;
+
+	/// UnregisterAdvertisement method
+	fn unregister_advertisement(
+		&self,
+		service: &zbus::zvariant::ObjectPath<'_>,
+	) -> zbus::Result<()>;
+
+	/// ActiveInstances property
+	#[zbus(property)]
+	fn active_instances(&self) -> zbus::Result<u8>;
+
+	/// SupportedIncludes property
+	#[zbus(property)]
+	fn supported_includes(&self) -> zbus::Result<Vec<String>>;
+
+	/// SupportedInstances property
+	#[zbus(property)]
+	fn supported_instances(&self) -> zbus::Result<u8>;
+
+	/// SupportedSecondaryChannels property
+	#[zbus(property)]
+	fn supported_secondary_channels(&self) -> zbus::Result<Vec<String>>;
+}
+
+impl LEAdvertisingManager1Proxy<'_> {
+	/// Registers the advertisement at `advertisement_path` without any options.
+	pub async fn register(
+		&self,
+		advertisement_path: &zbus::zvariant::ObjectPath<'_>,
+	) -> zbus::Result<()> {
+		self.register_advertisement(advertisement_path, std::collections::HashMap::new())
+			.await
+	}
+}
+
+/// Advertisements registered with one adapter at distinct paths.
+///
+/// Registering more advertisements than the controller has instances for fails
+/// with [`crate::BluezError::AdvertisementLimit`] instead of an opaque `Failed`.
+#[derive(Debug)]
+pub struct AdvertisementSet {
+	manager: LEAdvertisingManager1Proxy<'static>,
+	base_path: zbus::zvariant::OwnedObjectPath,
+	next_id: u32,
+	registered: Vec<zbus::zvariant::OwnedObjectPath>,
+	method_timeout: Option<std::time::Duration>,
+}
+
+impl AdvertisementSet {
+	/// Creates an empty set for the adapter at `adapter_path`. Advertisements
+	/// are exported at `<base_path>/advertisementN`.
+	pub async fn new(
+		connection: &zbus::Connection,
+		adapter_path: &zbus::zvariant::ObjectPath<'_>,
+		base_path: zbus::zvariant::OwnedObjectPath,
+	) -> zbus::Result<Self> {
+		let manager = LEAdvertisingManager1Proxy::builder(connection)
+			.path(adapter_path.to_owned())?
+			.build()
+			.await?;
+
+		Ok(Self {
+			manager,
+			base_path,
+			next_id: 0,
+			registered: Vec::new(),
+			method_timeout: None,
+		})
+	}
+
+	/// Bounds the `RegisterAdvertisement` call of [`add`](Self::add) by
+	/// `timeout`, after which it fails with [`crate::BluezError::Timeout`].
+	///
+	/// As for [`crate::BluetoothDevice::with_timeout`], the call otherwise
+	/// waits as long as BlueZ takes.
+	pub fn with_timeout(mut self, timeout: std::time::Duration) -> Self {
+		self.method_timeout = Some(timeout);
+		self
+	}
+
+	/// Exports `advertisement` and registers it, returning its path.
+	pub async fn add(
+		&mut self,
+		advertisement: crate::le_advertisement1::Advertisement,
+	) -> Result<zbus::zvariant::OwnedObjectPath, crate::BluezError> {
+		let supported = self.manager.supported_instances().await?;
+		let active = self.manager.active_instances().await?;
+		if active >= supported {
+			return Err(crate::BluezError::AdvertisementLimit { supported });
+		}
+
+		let path = zbus::zvariant::OwnedObjectPath::try_from(format!(
+			"{}/advertisement{}",
+			self.base_path.as_str(),
+			self.next_id
+		))
+		.map_err(zbus::Error::from)?;
+		self.next_id += 1;
+
+		let connection = self.manager.inner().connection();
+		crate::serve_at(connection, path.as_str(), advertisement).await?;
+		if let Err(why) = crate::timed(self.method_timeout, self.manager.register(&path)).await {
+			_ = connection
+				.object_server()
+				.remove::<crate::le_advertisement1::Advertisement, _>(path.as_str())
+				.await;
+			return Err(why);
+		}
+
+		self.registered.push(path.clone());
+		Ok(path)
+	}
+
+	/// Unregisters the advertisement at `path` and removes it from the bus.
+	pub async fn remove(&mut self, path: &zbus::zvariant::ObjectPath<'_>) -> zbus::Result<()> {
+		self.registered
+			.retain(|registered| registered.as_str() != path.as_str());
+
+		let unregistered = self.manager.unregister_advertisement(path).await;
+		_ = self
+			.manager
+			.inner()
+			.connection()
+			.object_server()
+			.remove::<crate::le_advertisement1::Advertisement, _>(path)
+			.await;
+		unregistered
+	}
+
+	/// Unregisters every advertisement of the set.
+	pub async fn clear(&mut self) -> zbus::Result<()> {
+		let mut result = Ok(());
+		for path in std::mem::take(&mut self.registered) {
+			if let Err(why) = self.remove(&path).await {
+				result = Err(why);
+			}
+		}
+		result
+	}
+
+	/// Paths of the registered advertisements, in the order they were added.
+	pub fn paths(&self) -> &[zbus::zvariant::OwnedObjectPath] {
+		&self.registered
+	}
+}
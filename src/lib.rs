@@ -2,16 +2,75 @@
 // SPDX-License-Identifier: MPL-2.0
 
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
-use futures_util::join;
+use futures_util::{join, Stream, StreamExt};
 
 pub mod adapter1;
+pub mod address;
+pub mod advertisement_monitor1;
+pub mod advertisement_monitor_manager1;
+pub mod advertising;
 pub mod agent1;
 pub mod agent_manager1;
+pub mod appearance;
 pub mod battery1;
+pub mod changes;
+pub mod class;
+pub mod daemon;
 pub mod device1;
+pub mod discovery;
+pub mod error;
+pub mod gatt;
+pub mod gatt_characteristic1;
+pub mod gatt_descriptor1;
+pub mod gatt_manager1;
+pub mod gatt_server;
+pub mod gatt_service1;
 pub mod health_manager1;
+pub mod input1;
+pub mod le_advertisement1;
+pub mod le_advertising_manager1;
+pub mod media1;
+pub mod media_control1;
+pub mod media_endpoint1;
+pub mod media_player1;
+pub mod media_transport1;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod modalias;
+pub mod network1;
+pub mod network_server1;
+pub mod objects;
+pub mod obex;
+pub mod pairing;
+pub mod profile1;
 pub mod profile_manager1;
+pub mod properties;
+pub mod registry;
+pub mod uuid;
+pub mod wait;
+
+pub use changes::PropertyStreamExt;
+pub use daemon::{watch_service, ServiceState};
+pub use error::BluezError;
+pub use pairing::{pair_device, PairOptions, PairingMethod};
+pub use registry::Registry;
+pub use uuid::{Profile, Uuid};
+pub use wait::wait_for;
+
+static SYSTEM_CONNECTION: tokio::sync::OnceCell<zbus::Connection> =
+	tokio::sync::OnceCell::const_new();
+
+/// Connection to the system bus shared by every caller, opened on first use.
+///
+/// If opening the connection fails, the next call tries again.
+pub async fn system_connection() -> zbus::Result<zbus::Connection> {
+	SYSTEM_CONNECTION
+		.get_or_try_init(zbus::Connection::system)
+		.await
+		.cloned()
+}
 
 pub async fn get_adapters<'a>(
 	connection: &zbus::Connection,
@@ -59,6 +118,16 @@ pub async fn get_adapters<'a>(
 pub struct BluetoothDevice<'a> {
 	pub device: device1::Device1Proxy<'a>,
 	pub battery: Option<battery1::Battery1Proxy<'a>>,
+	gatt_cache: Option<Arc<Mutex<GattCache>>>,
+	method_timeout: Option<std::time::Duration>,
+}
+
+/// Tree kept by [`BluetoothDevice::with_gatt_cache`]. `generation` counts the
+/// invalidations, so that a tree walked across one of them is not stored.
+#[derive(Debug, Default)]
+struct GattCache {
+	generation: u64,
+	services: Option<Vec<gatt::GattService<'static>>>,
 }
 
 impl<'a> BluetoothDevice<'a> {
@@ -79,20 +148,54 @@ impl<'a> BluetoothDevice<'a> {
 			(Ok(device), Ok(battery)) if battery.percentage().await.is_err() => Ok(Self {
 				device,
 				battery: None,
+				gatt_cache: None,
+				method_timeout: None,
 			}),
 			(Ok(device), Ok(battery)) => Ok(Self {
 				device,
 				battery: Some(battery),
+				gatt_cache: None,
+				method_timeout: None,
 			}),
 			(Ok(device), Err(zbus::Error::InterfaceNotFound)) => Ok(Self {
 				device,
 				battery: None,
+				gatt_cache: None,
+				method_timeout: None,
 			}),
 			(Err(why), _) => Err(why),
 			(_, Err(why)) => Err(why),
 		}
 	}
 
+	/// Bounds [`connect`](Self::connect), [`disconnect`](Self::disconnect), the
+	/// profile variants, [`ensure_paired`](Self::ensure_paired) and
+	/// [`forget`](Self::forget) by `timeout`, after which they fail with
+	/// [`BluezError::Timeout`].
+	///
+	/// By default these calls wait as long as BlueZ takes: zbus puts no timeout
+	/// of its own on method calls, so a pairing or connect that takes 30 seconds
+	/// or more never fails on the client side. This timeout can only shorten
+	/// that wait, not extend a reply timeout enforced by the bus daemon.
+	pub fn with_timeout(mut self, timeout: std::time::Duration) -> Self {
+		self.method_timeout = Some(timeout);
+		self
+	}
+
+	/// Name to show for the device: the alias if set, then the remote name,
+	/// then the address.
+	pub async fn display_name(&self) -> zbus::Result<String> {
+		for name in [self.device.alias().await, self.device.name().await] {
+			if let Some(name) = error::optional_property(name)? {
+				if !name.is_empty() {
+					return Ok(name);
+				}
+			}
+		}
+
+		self.device.address().await
+	}
+
 	pub async fn icon(&self) -> String {
 		self.device
 			.inner()
@@ -101,6 +204,474 @@ impl<'a> BluetoothDevice<'a> {
 			.unwrap_or("unknown".to_owned())
 	}
 
+	/// Reads all `org.bluez.Device1` properties with a single D-Bus call.
+	pub async fn properties(&self) -> zbus::Result<properties::DeviceProperties> {
+		let properties = properties::get_all(
+			self.device.inner().connection(),
+			self.device.inner().path(),
+			"org.bluez.Device1",
+		)
+		.await?;
+		Ok(properties::DeviceProperties::from_dict(&properties))
+	}
+
+	/// Signal strength of the last inquiry or advertisement, or `None` when the
+	/// device is not being discovered.
+	pub async fn rssi(&self) -> zbus::Result<Option<i16>> {
+		error::optional_property(self.device.rssi().await)
+	}
+
+	/// Advertised transmission power level, or `None` when not advertised.
+	pub async fn tx_power(&self) -> zbus::Result<Option<i16>> {
+		error::optional_property(self.device.tx_power().await)
+	}
+
+	/// Stream of `RSSI` updates, starting with the current value if there is one.
+	pub async fn rssi_stream(&self) -> impl Stream<Item = i16> + 'a {
+		self.device
+			.receive_rssi_changed()
+			.await
+			.values()
+	}
+
+	/// Stream of `RSSI` smoothed by an exponentially weighted moving average
+	/// over roughly the last `window` readings, in dBm.
+	///
+	/// The first reading is passed through unchanged. When the device goes
+	/// out of range and `RSSI` disappears, the average is reset so the next
+	/// reading starts afresh instead of being dragged down by stale values.
+	pub async fn smoothed_rssi(&self, window: usize) -> impl Stream<Item = f64> + 'a {
+		let alpha = 2.0 / (window.max(1) as f64 + 1.0);
+		let mut average: Option<f64> = None;
+
+		self.device
+			.receive_rssi_changed()
+			.await
+			.then(|change| async move { change.get().await.ok() })
+			.filter_map(move |rssi| {
+				average = rssi.map(|rssi| {
+					let rssi = f64::from(rssi);
+					average.map_or(rssi, |average| average + alpha * (rssi - average))
+				});
+				futures_util::future::ready(average)
+			})
+	}
+
+	/// Battery level in percent, or `None` if the device does not implement `org.bluez.Battery1`.
+	pub async fn battery_percentage(&self) -> zbus::Result<Option<u8>> {
+		match &self.battery {
+			Some(battery) => battery.percentage().await.map(Some),
+			None => Ok(None),
+		}
+	}
+
+	/// Manufacturer specific data from the last advertisement, keyed by company identifier.
+	///
+	/// This is empty when the device did not advertise any.
+	pub async fn manufacturer_data(&self) -> zbus::Result<HashMap<u16, Vec<u8>>> {
+		let manufacturer_data =
+			error::optional_property(self.device.manufacturer_data().await)?.unwrap_or_default();
+		Ok(decode_manufacturer_data(manufacturer_data))
+	}
+
+	/// Stream of `ManufacturerData` updates, keyed by company identifier, as
+	/// beacons change the payload of their advertisements.
+	pub async fn manufacturer_data_changes(&self) -> impl Stream<Item = HashMap<u16, Vec<u8>>> + 'a {
+		self.device
+			.receive_manufacturer_data_changed()
+			.await
+			.values()
+			.map(decode_manufacturer_data)
+	}
+
+	/// Flags from the last advertisement, or `None` when the device did not advertise any.
+	pub async fn advertising_flags(&self) -> zbus::Result<Option<advertising::AdvertisingFlags>> {
+		let flags = error::optional_property(self.device.advertising_flags().await)?;
+		Ok(flags
+			.and_then(|flags| flags.first().copied())
+			.map(advertising::AdvertisingFlags::from))
+	}
+
+	/// Service data from the last advertisement, keyed by service UUID.
+	///
+	/// This is empty when the device did not advertise any.
+	pub async fn service_data(&self) -> zbus::Result<HashMap<Uuid, Vec<u8>>> {
+		let service_data =
+			error::optional_property(self.device.service_data().await)?.unwrap_or_default();
+		Ok(service_data
+			.into_iter()
+			.filter_map(|(uuid, data)| Some((uuid.parse().ok()?, Vec::<u8>::try_from(data).ok()?)))
+			.collect())
+	}
+
+	pub async fn address_type(&self) -> zbus::Result<address::AddressType> {
+		let address_type = self.device.address_type().await?;
+		address::AddressType::try_from(address_type.as_str()).map_err(zbus::Error::Variant)
+	}
+
+	/// Service UUIDs of the device, skipping any that fail to parse.
+	pub async fn uuids(&self) -> zbus::Result<Vec<Uuid>> {
+		let uuids = error::optional_property(self.device.uuids().await)?.unwrap_or_default();
+		Ok(uuids.iter().filter_map(|uuid| uuid.parse().ok()).collect())
+	}
+
+	/// Whether the device lists `uuid` among its services.
+	pub async fn has_service(&self, uuid: Uuid) -> zbus::Result<bool> {
+		Ok(self.uuids().await?.contains(&uuid))
+	}
+
+	/// Parsed Device ID of the device, or `None` for devices that do not report one.
+	pub async fn modalias(&self) -> zbus::Result<Option<modalias::Modalias>> {
+		let Some(modalias) = error::optional_property(self.device.modalias().await)? else {
+			return Ok(None);
+		};
+		modalias
+			.parse()
+			.map(Some)
+			.map_err(|why: modalias::InvalidModalias| zbus::Error::Failure(why.to_string()))
+	}
+
+	/// Decoded GAP appearance, or `None` for devices that do not advertise one.
+	pub async fn appearance(&self) -> zbus::Result<Option<appearance::Appearance>> {
+		Ok(error::optional_property(self.device.appearance().await)?
+			.map(appearance::Appearance::from))
+	}
+
+	/// Decoded Class of Device, or `None` for devices that do not report one, such as LE-only devices.
+	pub async fn class(&self) -> zbus::Result<Option<class::DeviceClass>> {
+		Ok(error::optional_property(self.device.class().await)?.map(class::DeviceClass::from))
+	}
+
+	pub async fn is_trusted(&self) -> zbus::Result<bool> {
+		self.device.trusted().await
+	}
+
+	/// Marks the device as trusted, so that BlueZ accepts its incoming
+	/// connections without asking the agent for authorization.
+	pub async fn set_trusted(&self, trusted: bool) -> zbus::Result<()> {
+		self.device.set_trusted(trusted).await
+	}
+
+	/// Whether the device is allowed to wake the host from suspend, or `None`
+	/// when the device does not support waking it.
+	pub async fn wake_allowed(&self) -> zbus::Result<Option<bool>> {
+		error::optional_property(self.device.wake_allowed().await)
+	}
+
+	pub async fn set_wake_allowed(&self, allowed: bool) -> zbus::Result<()> {
+		self.device.set_wake_allowed(allowed).await
+	}
+
+	/// Coordinated sets the device is a member of, empty when it belongs to none.
+	pub async fn sets(&self) -> zbus::Result<Vec<device1::DeviceSet>> {
+		let Some(sets) = error::optional_property(self.device.sets().await)? else {
+			return Ok(Vec::new());
+		};
+
+		Ok(sets
+			.into_iter()
+			.map(|(path, properties)| device1::DeviceSet {
+				path,
+				rank: properties
+					.get("Rank")
+					.and_then(|rank| u8::try_from(rank).ok()),
+			})
+			.collect())
+	}
+
+	/// Whether the device only supports legacy PIN code pairing.
+	pub async fn legacy_pairing(&self) -> zbus::Result<bool> {
+		self.device.legacy_pairing().await
+	}
+
+	/// The pairing method that pairing with the device uses, so callers can
+	/// refuse devices that only support legacy pairing.
+	pub async fn pairing_method(&self) -> zbus::Result<PairingMethod> {
+		self.legacy_pairing().await.map(PairingMethod::from)
+	}
+
+	/// Pairs with the device unless it is already paired.
+	///
+	/// Pairing goes through the default agent, see [`pair_device`] to pair
+	/// with a specific one. A pairing completed by someone else in the
+	/// meantime, reported as `AlreadyExists`, counts as success.
+	pub async fn ensure_paired(&self) -> Result<(), BluezError> {
+		if self.device.paired().await? {
+			return Ok(());
+		}
+
+		match timed(self.method_timeout, self.device.pair()).await {
+			Ok(()) | Err(BluezError::AlreadyExists) => Ok(()),
+			Err(why) => Err(why),
+		}
+	}
+
+	pub async fn connect(&self) -> Result<(), BluezError> {
+		timed(self.method_timeout, self.device.connect()).await
+	}
+
+	pub async fn disconnect(&self) -> Result<(), BluezError> {
+		timed(self.method_timeout, self.device.disconnect()).await
+	}
+
+	pub async fn connect_profile(&self, uuid: &str) -> Result<(), BluezError> {
+		timed(self.method_timeout, self.device.connect_profile(uuid)).await
+	}
+
+	pub async fn disconnect_profile(&self, uuid: &str) -> Result<(), BluezError> {
+		timed(self.method_timeout, self.device.disconnect_profile(uuid)).await
+	}
+
+	/// Connects `profile`, named after the role the device implements: use
+	/// [`uuid::Profile::A2dpSink`] to stream audio to headphones.
+	pub async fn connect_profile_named(&self, profile: uuid::Profile) -> Result<(), BluezError> {
+		self.connect_profile(&profile.uuid().to_string()).await
+	}
+
+	pub async fn disconnect_profile_named(&self, profile: uuid::Profile) -> Result<(), BluezError> {
+		self.disconnect_profile(&profile.uuid().to_string()).await
+	}
+
+	/// Connects, retrying up to `attempts` times in total while BlueZ reports
+	/// `ConnectionAttemptFailed`. The delay between attempts starts at `backoff`
+	/// and doubles after every failure.
+	#[tracing::instrument(skip(self), fields(device = %self.device.inner().path().as_str()))]
+	pub async fn connect_with_retry(
+		&self,
+		attempts: u32,
+		backoff: std::time::Duration,
+	) -> Result<(), BluezError> {
+		let mut delay = backoff;
+		let mut attempt = 1;
+		loop {
+			match self.connect().await {
+				Ok(()) | Err(BluezError::AlreadyConnected) => return Ok(()),
+				Err(BluezError::ConnectionAttemptFailed) if attempt < attempts => {
+					tracing::debug!(attempt, ?delay, "connection attempt failed, retrying");
+					tokio::time::sleep(delay).await;
+					delay = delay.saturating_mul(2);
+					attempt += 1;
+				}
+				Err(why) => return Err(why),
+			}
+		}
+	}
+
+	/// Waits until the device reports `Connected`, failing with
+	/// [`BluezError::Timeout`] if that takes longer than `timeout`.
+	pub async fn wait_connected(&self, timeout: std::time::Duration) -> Result<(), BluezError> {
+		let changes = self.device.receive_connected_changed().await;
+		if self.device.connected().await? {
+			return Ok(());
+		}
+
+		let values = changes.values();
+		wait_for(values, |connected| *connected, timeout).await.map(drop)
+	}
+
+	/// Whether the GATT services of the device have been resolved.
+	pub async fn services_resolved(&self) -> zbus::Result<bool> {
+		self.device.services_resolved().await
+	}
+
+	/// Waits until the device reports `ServicesResolved`, failing with
+	/// [`BluezError::Timeout`] if that takes longer than `timeout`.
+	///
+	/// This returns immediately when the services are already resolved, as is
+	/// common when reconnecting to a bonded device. GATT services are only
+	/// complete once this resolves.
+	pub async fn wait_services_resolved(
+		&self,
+		timeout: std::time::Duration,
+	) -> Result<(), BluezError> {
+		let changes = self.device.receive_services_resolved_changed().await;
+		if self.services_resolved().await? {
+			return Ok(());
+		}
+
+		let values = changes.values();
+		wait_for(values, |resolved| *resolved, timeout).await.map(drop)
+	}
+
+	/// Connects, waits up to `timeout` for `ServicesResolved` and returns the GATT services.
+	///
+	/// If the services are not resolved in time, [`BluezError::Timeout`] is
+	/// returned, and the device is disconnected again if this call connected it.
+	/// A connection that already existed is left alone.
+	pub async fn connect_and_resolve(
+		&self,
+		timeout: std::time::Duration,
+	) -> Result<Vec<gatt::GattService<'a>>, BluezError> {
+		let was_connected = self.device.connected().await?;
+		let connected_here = match self.connect().await {
+			Ok(()) => !was_connected,
+			Err(BluezError::AlreadyConnected) => false,
+			Err(why) => return Err(why),
+		};
+
+		if let Err(why) = self.wait_services_resolved(timeout).await {
+			if !connected_here {
+				return Err(why);
+			}
+			if let Err(why) = self.disconnect().await {
+				tracing::warn!(?why, "failed to disconnect after service resolution failed");
+			}
+			return Err(why);
+		}
+
+		Ok(self.gatt_services().await?)
+	}
+
+	/// The adapter the device belongs to, from the `Adapter` property.
+	pub async fn adapter(&self) -> zbus::Result<BluetoothAdapter<'static>> {
+		let adapter_path = self.device.adapter().await?;
+		BluetoothAdapter::new(self.device.inner().connection(), adapter_path.into()).await
+	}
+
+	/// Forgets the device, disconnecting it and removing it from its adapter.
+	///
+	/// A failed disconnect does not prevent the removal, which tears the
+	/// connection down as well. Returns once the device object has left the
+	/// bus, or [`BluezError::Timeout`] if it is still there after `timeout`.
+	pub async fn forget(&self, timeout: std::time::Duration) -> Result<(), BluezError> {
+		match self.disconnect().await {
+			Ok(()) | Err(BluezError::NotConnected) => (),
+			Err(why) => tracing::warn!(?why, "failed to disconnect before removing the device"),
+		}
+
+		let adapter = self.adapter().await?;
+		let path = self.path();
+
+		// Subscribe before removing, as BlueZ drops the object before replying.
+		let events = objects::watch(self.device.inner().connection()).await?;
+		timed(self.method_timeout, adapter.adapter.remove_device(&path)).await?;
+
+		wait_for(
+			events,
+			|event| {
+				matches!(
+					event,
+					objects::ObjectEvent::Removed { path: removed, interfaces }
+						if *removed == path
+							&& interfaces.iter().any(|interface| interface.as_str() == "org.bluez.Device1")
+				)
+			},
+			timeout,
+		)
+		.await
+		.map(drop)
+	}
+
+	/// Keeps the tree resolved by [`BluetoothDevice::gatt_services`] until
+	/// `ServicesResolved` turns false, such as on disconnect, after which the
+	/// next call resolves it again.
+	///
+	/// The invalidation runs on a tokio task.
+	pub async fn with_gatt_cache(mut self) -> zbus::Result<Self> {
+		let device = device1::Device1Proxy::builder(self.device.inner().connection())
+			.path(self.path())?
+			.build()
+			.await?;
+		let mut services_resolved_changed = device.receive_services_resolved_changed().await;
+
+		let cache = Arc::new(Mutex::new(GattCache::default()));
+		let weak_cache = Arc::downgrade(&cache);
+		tokio::spawn(async move {
+			// Keeps the property cache feeding the stream alive.
+			let _device = device;
+			while let Some(change) = services_resolved_changed.next().await {
+				let resolved = change.get().await.unwrap_or(false);
+				let Some(cache) = weak_cache.upgrade() else {
+					break;
+				};
+				if !resolved {
+					let mut cache = cache.lock().unwrap();
+					cache.generation += 1;
+					cache.services = None;
+				}
+			}
+		});
+
+		self.gatt_cache = Some(cache);
+		Ok(self)
+	}
+
+	/// Resolves the GATT services of this device.
+	///
+	/// This is only complete once `ServicesResolved` is true. With
+	/// [`BluetoothDevice::with_gatt_cache`], a complete tree is resolved only once.
+	pub async fn gatt_services(&self) -> zbus::Result<Vec<gatt::GattService<'a>>> {
+		let connection = self.device.inner().connection();
+		let path = self.device.inner().path();
+		let Some(cache) = &self.gatt_cache else {
+			return gatt::gatt_services(connection, path).await;
+		};
+
+		let generation = {
+			let cache = cache.lock().unwrap();
+			if let Some(services) = &cache.services {
+				return Ok(services.clone());
+			}
+			cache.generation
+		};
+
+		// Checked before walking, so that a partial tree is never stored. If
+		// `ServicesResolved` turns false during the walk, the generation moves on.
+		let resolved = self.device.services_resolved().await?;
+		let services: Vec<gatt::GattService<'static>> =
+			gatt::gatt_services(connection, path).await?;
+		let mut cache = cache.lock().unwrap();
+		if resolved && cache.generation == generation {
+			cache.services = Some(services.clone());
+		}
+		Ok(services)
+	}
+
+	/// Finds the characteristic `characteristic_uuid` of the service `service_uuid`
+	/// among the resolved GATT services.
+	pub async fn characteristic(
+		&self,
+		service_uuid: Uuid,
+		characteristic_uuid: Uuid,
+	) -> zbus::Result<Option<gatt::GattCharacteristic<'a>>> {
+		for service in self.gatt_services().await? {
+			if service.service.uuid().await?.parse::<Uuid>().ok() != Some(service_uuid) {
+				continue;
+			}
+
+			for characteristic in service.characteristics {
+				let uuid = characteristic.characteristic.uuid().await?;
+				if uuid.parse::<Uuid>().ok() == Some(characteristic_uuid) {
+					return Ok(Some(characteristic));
+				}
+			}
+		}
+
+		Ok(None)
+	}
+
+	/// Stream of changes to the `org.bluez.Device1` properties of this device.
+	pub async fn changes(&self) -> zbus::Result<impl Stream<Item = changes::DeviceChange>> {
+		let properties = zbus::fdo::PropertiesProxy::builder(self.device.inner().connection())
+			.destination("org.bluez")?
+			.path(self.device.inner().path().to_owned())?
+			.build()
+			.await?;
+		let properties_changed = properties.receive_properties_changed().await?;
+
+		Ok(properties_changed.flat_map(|signal| {
+			let changes: Vec<changes::DeviceChange> = match signal.args() {
+				Ok(args) if args.interface_name().as_str() == "org.bluez.Device1" => args
+					.changed_properties()
+					.iter()
+					.filter_map(|(name, value)| changes::DeviceChange::decode(name, value))
+					.collect(),
+				_ => Vec::new(),
+			};
+			futures_util::stream::iter(changes)
+		}))
+	}
+
 	pub fn path(&self) -> zbus::zvariant::OwnedObjectPath {
 		self.device.inner().path().to_owned().into()
 	}
@@ -113,18 +684,480 @@ pub async fn get_device<'a>(
 	BluetoothDevice::new(connection, device_path.into()).await
 }
 
+/// Looks up the device with the MAC address `address`, such as `AA:BB:CC:DD:EE:FF`,
+/// among the devices known to the adapter at `adapter_path`.
+pub async fn get_device_by_address<'a>(
+	connection: &zbus::Connection,
+	adapter_path: &zbus::zvariant::ObjectPath<'_>,
+	address: &str,
+) -> zbus::Result<BluetoothDevice<'a>> {
+	let device_path = device_path(adapter_path, address)
+		.ok_or_else(|| zbus::Error::Failure(format!("Invalid device address {address}")))?;
+
+	let managed_object = objects::managed_objects(connection).await?;
+	let exists = managed_object
+		.get(&device_path)
+		.is_some_and(|interfaces| interfaces.contains_key("org.bluez.Device1"));
+	if !exists {
+		return Err(zbus::Error::Failure(format!("No device found with address {address}")));
+	}
+
+	BluetoothDevice::new(connection, device_path.into()).await
+}
+
+fn decode_manufacturer_data(
+	manufacturer_data: HashMap<u16, zbus::zvariant::OwnedValue>,
+) -> HashMap<u16, Vec<u8>> {
+	manufacturer_data
+		.into_iter()
+		.filter_map(|(company, data)| Some((company, Vec::<u8>::try_from(data).ok()?)))
+		.collect()
+}
+
+/// Builds the path BlueZ exports a device at, `<adapter>/dev_AA_BB_CC_DD_EE_FF`.
+pub(crate) fn device_path(
+	adapter_path: &zbus::zvariant::ObjectPath<'_>,
+	address: &str,
+) -> Option<zbus::zvariant::OwnedObjectPath> {
+	let octets: Vec<&str> = address.split(':').collect();
+	let valid = octets.len() == 6
+		&& octets
+			.iter()
+			.all(|octet| octet.len() == 2 && octet.chars().all(|c| c.is_ascii_hexdigit()));
+	if !valid {
+		return None;
+	}
+
+	let path = format!(
+		"{}/dev_{}",
+		adapter_path.as_str(),
+		octets.join("_").to_ascii_uppercase()
+	);
+	zbus::zvariant::OwnedObjectPath::try_from(path).ok()
+}
+
+#[derive(Debug)]
+pub struct BluetoothAdapter<'a> {
+	pub adapter: adapter1::Adapter1Proxy<'a>,
+	connect_timeout: Option<std::time::Duration>,
+}
+
+impl<'a> BluetoothAdapter<'a> {
+	pub async fn new<'b: 'a>(
+		connection: &zbus::Connection,
+		path: zbus::zvariant::ObjectPath<'b>,
+	) -> zbus::Result<Self> {
+		let managed_object = objects::managed_objects(connection).await?;
+		let exists = managed_object.iter().any(|(object_path, interfaces)| {
+			object_path.as_str() == path.as_str() && interfaces.contains_key("org.bluez.Adapter1")
+		});
+		if !exists {
+			return Err(zbus::Error::Failure(format!("No adapter found at {path}")));
+		}
+
+		let adapter = adapter1::Adapter1Proxy::builder(connection)
+			.path(path)?
+			.build()
+			.await?;
+
+		Ok(Self {
+			adapter,
+			connect_timeout: None,
+		})
+	}
+
+	/// Bounds [`connect_device`](Self::connect_device) by `timeout`, after which
+	/// it fails with [`BluezError::Timeout`]. Other methods are not affected.
+	///
+	/// Like [`BluetoothDevice::with_timeout`], this can only shorten the wait,
+	/// which is otherwise unbounded on the client side.
+	pub fn with_connect_timeout(mut self, timeout: std::time::Duration) -> Self {
+		self.connect_timeout = Some(timeout);
+		self
+	}
+
+	/// Powers the adapter on and waits until it reports `Powered`, failing with
+	/// [`BluezError::Timeout`] if that takes longer than `timeout`.
+	pub async fn power_on(&self, timeout: std::time::Duration) -> Result<(), BluezError> {
+		self.set_powered(true, timeout).await
+	}
+
+	/// Powers the adapter off and waits until it no longer reports `Powered`.
+	pub async fn power_off(&self, timeout: std::time::Duration) -> Result<(), BluezError> {
+		self.set_powered(false, timeout).await
+	}
+
+	async fn set_powered(&self, on: bool, timeout: std::time::Duration) -> Result<(), BluezError> {
+		// Subscribe before writing so that the change is not missed.
+		let changes = self.adapter.receive_powered_changed().await;
+		self.adapter.set_powered(on).await?;
+		if self.adapter.powered().await? == on {
+			return Ok(());
+		}
+
+		let values = changes.values();
+		wait_for(values, |powered| *powered == on, timeout).await.map(drop)
+	}
+
+	/// The Bluetooth address of the controller.
+	pub async fn address(&self) -> zbus::Result<String> {
+		self.adapter.address().await
+	}
+
+	/// Whether the controller uses its public address or a static random one.
+	pub async fn address_type(&self) -> zbus::Result<address::AddressType> {
+		let address_type = self.adapter.address_type().await?;
+		address::AddressType::try_from(address_type.as_str()).map_err(zbus::Error::Variant)
+	}
+
+	/// The friendly name of the adapter, which is the system name unless it was
+	/// changed with [`BluetoothAdapter::set_alias`].
+	pub async fn alias(&self) -> zbus::Result<String> {
+		self.adapter.alias().await
+	}
+
+	/// Renames the adapter as seen by remote devices. An empty alias restores the system name.
+	pub async fn set_alias(&self, alias: &str) -> zbus::Result<()> {
+		self.adapter.set_alias(alias).await
+	}
+
+	/// The system name of the adapter, which is read-only.
+	pub async fn name(&self) -> zbus::Result<String> {
+		self.adapter.name().await
+	}
+
+	/// Roles supported by the controller, skipping any this crate does not know.
+	///
+	/// This is empty with BlueZ versions that do not report `Roles`.
+	pub async fn roles(&self) -> zbus::Result<Vec<adapter1::Role>> {
+		let roles = error::optional_property(self.adapter.roles().await)?.unwrap_or_default();
+		Ok(roles
+			.iter()
+			.filter_map(|role| adapter1::Role::try_from(role.as_str()).ok())
+			.collect())
+	}
+
+	/// Whether the controller supports `role`, to check before advertising
+	/// or registering GATT applications.
+	pub async fn supports_role(&self, role: adapter1::Role) -> zbus::Result<bool> {
+		Ok(self.roles().await?.contains(&role))
+	}
+
+	/// UUIDs of the profiles the local adapter provides, such as A2DP or HFP.
+	pub async fn uuids(&self) -> zbus::Result<Vec<Uuid>> {
+		let uuids = error::optional_property(self.adapter.uuids().await)?.unwrap_or_default();
+		Ok(uuids.iter().filter_map(|uuid| uuid.parse().ok()).collect())
+	}
+
+	/// Whether the local adapter provides the profile `uuid`.
+	pub async fn has_service(&self, uuid: Uuid) -> zbus::Result<bool> {
+		Ok(self.uuids().await?.contains(&uuid))
+	}
+
+	/// UUIDs of the experimental features enabled in `bluetoothd`.
+	pub async fn experimental_features(&self) -> zbus::Result<Vec<Uuid>> {
+		let features =
+			error::optional_property(self.adapter.experimental_features().await)?.unwrap_or_default();
+		Ok(features.iter().filter_map(|feature| feature.parse().ok()).collect())
+	}
+
+	/// Sets the discovery filter, failing with [`BluezError::InvalidDiscoveryFilter`]
+	/// without calling BlueZ if the filter does not pass
+	/// [`discovery::DiscoveryFilter::validate`].
+	pub async fn set_discovery_filter(
+		&self,
+		filter: discovery::DiscoveryFilter,
+	) -> Result<(), BluezError> {
+		let dict = filter.to_dict()?;
+		self.adapter
+			.set_discovery_filter(dict.iter().map(|(key, value)| (key.as_str(), value)).collect())
+			.await?;
+		Ok(())
+	}
+
+	/// Sets `Discoverable`, writing `DiscoverableTimeout` first so the adapter
+	/// never ends up discoverable with a stale timeout. `None` disables the timeout.
+	pub async fn set_discoverable(
+		&self,
+		on: bool,
+		timeout: Option<std::time::Duration>,
+	) -> zbus::Result<()> {
+		self.adapter
+			.set_discoverable_timeout(timeout_secs(timeout))
+			.await?;
+		self.adapter.set_discoverable(on).await
+	}
+
+	/// Sets `Pairable`, writing `PairableTimeout` first so the adapter never
+	/// ends up pairable with a stale timeout. `None` disables the timeout.
+	pub async fn set_pairable(
+		&self,
+		on: bool,
+		timeout: Option<std::time::Duration>,
+	) -> zbus::Result<()> {
+		self.adapter
+			.set_pairable_timeout(timeout_secs(timeout))
+			.await?;
+		self.adapter.set_pairable(on).await
+	}
+
+	/// Whether the adapter is discovering, including discovery started by other clients.
+	pub async fn discovering(&self) -> zbus::Result<bool> {
+		self.adapter.discovering().await
+	}
+
+	/// Stream of `Discovering` updates, starting with the current value.
+	pub async fn discovering_changes(&self) -> impl Stream<Item = bool> + 'a {
+		self.adapter
+			.receive_discovering_changed()
+			.await
+			.values()
+	}
+
+	/// Brings the adapter into a state where other devices can find and pair
+	/// with it: powered, pairable, and discoverable for `discoverable_timeout`.
+	///
+	/// The adapter is powered first, waiting up to `power_timeout`, since BlueZ
+	/// rejects the other settings on an adapter that is still powering up.
+	pub async fn prepare_for_pairing(
+		&self,
+		power_timeout: std::time::Duration,
+		discoverable_timeout: Option<std::time::Duration>,
+	) -> Result<(), BluezError> {
+		self.power_on(power_timeout).await?;
+		self.set_pairable(true, None).await?;
+		self.set_discoverable(true, discoverable_timeout).await?;
+		Ok(())
+	}
+
+	/// Devices known to this adapter, that is the objects directly below its path.
+	pub async fn devices(&self) -> zbus::Result<Vec<BluetoothDevice<'a>>> {
+		objects::devices_for_adapter(self.adapter.inner().connection(), self.adapter.inner().path())
+			.await
+	}
+
+	/// Connects to the device at `address` without discovering it first.
+	///
+	/// This uses the experimental `ConnectDevice` method, so `bluetoothd` must
+	/// run with experimental features enabled.
+	pub async fn connect_device(
+		&self,
+		address: &str,
+		address_type: address::AddressType,
+	) -> Result<BluetoothDevice<'a>, BluezError> {
+		let address = zbus::zvariant::Value::from(address);
+		let address_type = zbus::zvariant::Value::from(<&'static str>::from(address_type));
+		let device_path = timed(
+			self.connect_timeout,
+			self.adapter.connect_device(HashMap::from([
+				("Address", &address),
+				("AddressType", &address_type),
+			])),
+		)
+		.await?;
+
+		Ok(BluetoothDevice::new(self.adapter.inner().connection(), device_path.into()).await?)
+	}
+
+	/// Removes `device` and its pairing information from this adapter.
+	pub async fn remove_device(&self, device: &BluetoothDevice<'_>) -> zbus::Result<()> {
+		self.adapter.remove_device(device.device.inner().path()).await
+	}
+
+	/// Removes the device with the MAC address `address` from this adapter.
+	pub async fn remove_device_by_address(&self, address: &str) -> zbus::Result<()> {
+		let devices = self.devices().await?;
+
+		for device in devices {
+			if device.device.address().await?.eq_ignore_ascii_case(address) {
+				return self.remove_device(&device).await;
+			}
+		}
+
+		Err(zbus::Error::Failure(format!("No device found with address {address}")))
+	}
+
+	/// Removes every device known to this adapter, such as for a factory reset.
+	///
+	/// Each device is removed even if removing another one failed. Devices
+	/// that disappear on their own before their turn are reported as vanished
+	/// rather than as failures.
+	pub async fn remove_all_devices(&self) -> zbus::Result<RemovalSummary> {
+		let managed_object = objects::managed_objects(self.adapter.inner().connection()).await?;
+		let adapter_path = self.adapter.inner().path();
+		let device_paths = objects::paths_with_interface(&managed_object, "org.bluez.Device1")
+			.into_iter()
+			.filter(|path| {
+				path.as_str()
+					.rsplit_once('/')
+					.is_some_and(|(parent, _)| parent == adapter_path.as_str())
+			});
+
+		let mut summary = RemovalSummary::default();
+		for path in device_paths {
+			match self.adapter.remove_device(&path).await.map_err(BluezError::from) {
+				Ok(()) => summary.removed.push(path),
+				Err(BluezError::DoesNotExist) => summary.vanished.push(path),
+				Err(why) => {
+					tracing::warn!(?path, ?why, "failed to remove device");
+					summary.failed.push((path, why));
+				}
+			}
+		}
+
+		Ok(summary)
+	}
+
+	/// Scans for `duration` and returns the devices seen meanwhile, strongest signal first.
+	///
+	/// A device counts as seen when it has an `RSSI`, which BlueZ only reports
+	/// for devices found by the ongoing discovery.
+	pub async fn scan_for(
+		&self,
+		duration: std::time::Duration,
+	) -> Result<Vec<BluetoothDevice<'a>>, BluezError> {
+		let session = self.start_discovery().await?;
+		tokio::time::sleep(duration).await;
+
+		// Collect before stopping, since BlueZ clears the RSSI once discovery ends.
+		let seen = self.seen_devices().await;
+		let stopped = session.stop().await;
+		let mut seen = seen?;
+		stopped?;
+
+		seen.sort_by_key(|(rssi, _)| std::cmp::Reverse(*rssi));
+		Ok(seen.into_iter().map(|(_, device)| device).collect())
+	}
+
+	async fn seen_devices(&self) -> zbus::Result<Vec<(i16, BluetoothDevice<'a>)>> {
+		let devices = self.devices().await?;
+
+		let mut addresses = std::collections::HashSet::new();
+		let mut seen = Vec::new();
+		for device in devices {
+			let Some(rssi) = error::optional_property(device.device.rssi().await)? else {
+				continue;
+			};
+			if addresses.insert(device.device.address().await?) {
+				seen.push((rssi, device));
+			}
+		}
+
+		Ok(seen)
+	}
+
+	/// Starts discovery, which stops again when the returned session is dropped.
+	pub async fn start_discovery(&self) -> Result<discovery::DiscoverySession, BluezError> {
+		discovery::DiscoverySession::new(self.adapter.inner().connection(), self.path(), None).await
+	}
+
+	/// Starts discovery with `filter`, which is cleared again together with
+	/// stopping discovery when the returned session ends.
+	pub async fn start_filtered_discovery(
+		&self,
+		filter: &discovery::DiscoveryFilter,
+	) -> Result<discovery::DiscoverySession, BluezError> {
+		discovery::DiscoverySession::new(self.adapter.inner().connection(), self.path(), Some(filter))
+			.await
+	}
+
+	/// Starts discovery with `filter` and streams the devices that are found.
+	///
+	/// Pass [`discovery::DiscoveryFilter::default`] to discover without restrictions.
+	#[tracing::instrument(skip(self), fields(adapter = %self.adapter.inner().path().as_str()))]
+	pub async fn discover_devices(
+		&self,
+		filter: discovery::DiscoveryFilter,
+	) -> Result<discovery::DeviceDiscovery, BluezError> {
+		discovery::DeviceDiscovery::new(self.adapter.inner().connection(), self.path(), filter).await
+	}
+
+	pub fn path(&self) -> zbus::zvariant::OwnedObjectPath {
+		self.adapter.inner().path().to_owned().into()
+	}
+}
+
+/// Outcome of [`BluetoothAdapter::remove_all_devices`].
+#[derive(Debug, Default)]
+pub struct RemovalSummary {
+	pub removed: Vec<zbus::zvariant::OwnedObjectPath>,
+	/// Devices that were already gone when their removal was attempted.
+	pub vanished: Vec<zbus::zvariant::OwnedObjectPath>,
+	pub failed: Vec<(zbus::zvariant::OwnedObjectPath, BluezError)>,
+}
+
+impl RemovalSummary {
+	/// Whether no removal failed, so that every device is gone.
+	pub fn is_complete(&self) -> bool {
+		self.failed.is_empty()
+	}
+}
+
+/// Runs a method call, failing with [`BluezError::Timeout`] if it does not
+/// complete within `timeout`.
+pub(crate) async fn timed<T>(
+	timeout: Option<std::time::Duration>,
+	call: impl std::future::Future<Output = zbus::Result<T>>,
+) -> Result<T, BluezError> {
+	match timeout {
+		Some(timeout) => tokio::time::timeout(timeout, call)
+			.await
+			.map_err(|_| BluezError::Timeout)?
+			.map_err(BluezError::from),
+		None => call.await.map_err(BluezError::from),
+	}
+}
+
+/// Serves `interface` at `path` on `connection`, failing if `path` already
+/// serves an interface of the same name instead of silently keeping the
+/// existing one.
+pub(crate) async fn serve_at(
+	connection: &zbus::Connection,
+	path: &str,
+	interface: impl zbus::Interface,
+) -> zbus::Result<()> {
+	if connection.object_server().at(path, interface).await? {
+		Ok(())
+	} else {
+		Err(zbus::Error::Failure("object path already in use".to_owned()))
+	}
+}
+
+/// Converts a timeout into the seconds BlueZ expects, where 0 means no timeout.
+fn timeout_secs(timeout: Option<std::time::Duration>) -> u32 {
+	timeout.map_or(0, |timeout| {
+		u32::try_from(timeout.as_secs()).unwrap_or(u32::MAX).max(1)
+	})
+}
+
 pub async fn get_adapter<'a>(
 	connection: &zbus::Connection,
 	adapter_path: impl TryInto<zbus::zvariant::ObjectPath<'a>>,
-) -> zbus::Result<adapter1::Adapter1Proxy<'a>> {
-	adapter1::Adapter1Proxy::builder(connection)
-		.path(
-			adapter_path
-				.try_into()
-				.map_err(|_| zbus::Error::Failure("Invalid adapter path".to_owned()))?,
-		)?
-		.build()
-		.await
+) -> zbus::Result<BluetoothAdapter<'a>> {
+	let adapter_path = adapter_path
+		.try_into()
+		.map_err(|_| zbus::Error::Failure("Invalid adapter path".to_owned()))?;
+	BluetoothAdapter::new(connection, adapter_path).await
+}
+
+pub async fn default_adapter<'a>(
+	connection: &zbus::Connection,
+) -> zbus::Result<Option<BluetoothAdapter<'a>>> {
+	let mut adapters = objects::all_adapters(connection).await?;
+
+	let mut index = 0;
+	for (position, adapter) in adapters.iter().enumerate() {
+		if adapter.adapter.powered().await.unwrap_or(false) {
+			index = position;
+			break;
+		}
+	}
+
+	if index < adapters.len() {
+		Ok(Some(adapters.swap_remove(index)))
+	} else {
+		Ok(None)
+	}
 }
 
 pub async fn get_devices<'a>(
@@ -0,0 +1,228 @@
+//! Integrations for creating bluez media endpoints, such as A2DP sinks and sources.
+
+use std::collections::HashMap;
+
+use tokio::sync::{mpsc, oneshot};
+use zbus::zvariant::{OwnedObjectPath, OwnedValue, Value};
+
+pub fn create(params: Params) -> (Endpoint, mpsc::Receiver<Message>) {
+    let (message_sender, message_receiver) = tokio::sync::mpsc::channel(1);
+
+    (
+        Endpoint {
+            params,
+            message_sender,
+        },
+        message_receiver,
+    )
+}
+
+/// Properties of the endpoint, as passed to `org.bluez.Media1.RegisterEndpoint`.
+#[derive(Clone, Debug, Default)]
+pub struct Params {
+    /// UUID of the profile the endpoint is for, such as the A2DP sink UUID.
+    pub uuid: String,
+    /// Assigned number of the codec the endpoint supports.
+    pub codec: u8,
+    /// Codec specific capabilities.
+    pub capabilities: Vec<u8>,
+    pub delay_reporting: bool,
+}
+
+impl Params {
+    /// Serializes the parameters into the `a{sv}` dictionary BlueZ expects.
+    pub fn to_dict(&self) -> HashMap<String, Value<'static>> {
+        HashMap::from([
+            ("UUID".to_owned(), Value::from(self.uuid.clone())),
+            ("Codec".to_owned(), Value::from(self.codec)),
+            (
+                "Capabilities".to_owned(),
+                Value::from(self.capabilities.clone()),
+            ),
+            ("DelayReporting".to_owned(), Value::from(self.delay_reporting)),
+        ])
+    }
+}
+
+#[derive(Debug)]
+pub enum Message {
+    ClearConfiguration {
+        transport: OwnedObjectPath,
+    },
+    Release,
+    SelectConfiguration {
+        capabilities: Vec<u8>,
+        response: SelectConfigurationResponder,
+    },
+    SetConfiguration {
+        transport: OwnedObjectPath,
+        properties: HashMap<String, OwnedValue>,
+        response: SetConfigurationResponder,
+    },
+}
+
+/// Errors returned to BlueZ, in the `org.bluez.Error` namespace.
+#[derive(Debug, zbus::DBusError)]
+#[zbus(prefix = "org.bluez.Error")]
+pub enum EndpointError {
+    #[zbus(error)]
+    ZBus(zbus::Error),
+    /// The request was rejected by the endpoint.
+    Rejected(String),
+    /// The request was dropped without a response.
+    Canceled(String),
+}
+
+/// Reply to a `SelectConfiguration` request.
+#[derive(Debug)]
+pub struct SelectConfigurationResponder(oneshot::Sender<Option<Vec<u8>>>);
+
+impl SelectConfigurationResponder {
+    /// Replies with the configuration chosen from the offered capabilities.
+    pub fn accept(self, configuration: Vec<u8>) {
+        _ = self.0.send(Some(configuration));
+    }
+
+    /// Replies with `org.bluez.Error.Rejected`.
+    pub fn reject(self) {
+        _ = self.0.send(None);
+    }
+}
+
+/// Reply to a `SetConfiguration` request.
+#[derive(Debug)]
+pub struct SetConfigurationResponder(oneshot::Sender<Option<()>>);
+
+impl SetConfigurationResponder {
+    pub fn accept(self) {
+        _ = self.0.send(Some(()));
+    }
+
+    /// Replies with `org.bluez.Error.Rejected`.
+    pub fn reject(self) {
+        _ = self.0.send(None);
+    }
+}
+
+pub struct Endpoint {
+    params: Params,
+    pub(self) message_sender: mpsc::Sender<Message>,
+}
+
+#[zbus::interface(name = "org.bluez.MediaEndpoint1")]
+impl Endpoint {
+    /// Set configuration for the transport.
+    ///
+    /// For client role transport must be set with a server
+    /// endpoint object which will be configured and the
+    /// properties must contain the following properties.
+    async fn set_configuration(
+        &mut self,
+        transport: OwnedObjectPath,
+        properties: HashMap<String, OwnedValue>,
+    ) -> Result<(), EndpointError> {
+        tracing::debug!(?transport, "set_configuration");
+
+        let (response, response_rx) = oneshot::channel();
+
+        _ = self
+            .message_sender
+            .send(Message::SetConfiguration {
+                transport,
+                properties,
+                response: SetConfigurationResponder(response),
+            })
+            .await;
+
+        await_reply(response_rx).await
+    }
+
+    /// Select preferable configuration from the supported
+    /// capabilities.
+    ///
+    /// Returns a configuration which can be used to setup
+    /// a transport.
+    async fn select_configuration(
+        &mut self,
+        capabilities: Vec<u8>,
+    ) -> Result<Vec<u8>, EndpointError> {
+        tracing::debug!(?capabilities, "select_configuration");
+
+        let (response, response_rx) = oneshot::channel();
+
+        _ = self
+            .message_sender
+            .send(Message::SelectConfiguration {
+                capabilities,
+                response: SelectConfigurationResponder(response),
+            })
+            .await;
+
+        await_reply(response_rx).await
+    }
+
+    /// Clear transport configuration.
+    async fn clear_configuration(
+        &mut self,
+        transport: OwnedObjectPath,
+    ) -> zbus::fdo::Result<()> {
+        tracing::debug!(?transport, "clear_configuration");
+
+        _ = self
+            .message_sender
+            .send(Message::ClearConfiguration { transport })
+            .await;
+
+        Ok(())
+    }
+
+    /// This method gets called when the service daemon
+    /// unregisters the endpoint. An endpoint can use it to do
+    /// cleanup tasks. There is no need to unregister the
+    /// endpoint, because when this method gets called it has
+    /// already been unregistered.
+    async fn release(&mut self) -> zbus::fdo::Result<()> {
+        tracing::debug!("release");
+
+        _ = self.message_sender.send(Message::Release).await;
+
+        Ok(())
+    }
+
+    /// UUID of the profile which the endpoint is for.
+    #[zbus(property, name = "UUID")]
+    fn uuid(&self) -> String {
+        self.params.uuid.clone()
+    }
+
+    /// Assigned number of codec that the endpoint implements.
+    #[zbus(property)]
+    fn codec(&self) -> u8 {
+        self.params.codec
+    }
+
+    /// Capabilities blob, it is used as it is so the size and
+    /// byte order must match.
+    #[zbus(property)]
+    fn capabilities(&self) -> Vec<u8> {
+        self.params.capabilities.clone()
+    }
+
+    /// Indicates if endpoint supports Delay Reporting.
+    #[zbus(property)]
+    fn delay_reporting(&self) -> bool {
+        self.params.delay_reporting
+    }
+}
+
+/// Waits for a responder to be used, mapping a rejection or a dropped
+/// responder to the matching BlueZ error.
+async fn await_reply<T>(response_rx: oneshot::Receiver<Option<T>>) -> Result<T, EndpointError> {
+    match response_rx.await {
+        Ok(Some(value)) => Ok(value),
+        Ok(None) => Err(EndpointError::Rejected("rejected by endpoint".to_owned())),
+        Err(_) => Err(EndpointError::Canceled(
+            "request dropped by endpoint".to_owned(),
+        )),
+    }
+}
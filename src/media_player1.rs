@@ -0,0 +1,130 @@
+//! # D-Bus interface proxy for: `org.bluez.MediaPlayer1`
+//!
+//! This code was generated by `zbus-xmlgen` `4.1.0` from D-Bus introspection data.
+//! Source: `Interface '/org/bluez/hci0/dev_14_3F_A6_A8_16_68/player0' from service 'org.bluez' on system bus`.
+//!
+//! You may prefer to adapt it, instead of using it verbatim.
+//!
+//! More information can be found in the [Writing a client proxy] section of the zbus
+//! documentation.
+//!
+//! This type implements the [D-Bus standard interfaces], (`org.freedesktop.DBus.*`) for which the
+//! following zbus API can be used:
+//!
+//! * [`zbus::fdo::IntrospectableProxy`]
+//! * [`zbus::fdo::PropertiesProxy`]
+//!
+//! Consequently `zbus-xmlgen` did not generate code for the above interfaces.
+//!
+//! [Writing a client proxy]: https://dbus2.github.io/zbus/client.html
+//! [D-Bus standard interfaces]: https://dbus.freedesktop.org/doc/dbus-specification.html#standard-interfaces,
+use std::collections::HashMap;
+
+use zbus::proxy;
+use zbus::zvariant::OwnedValue;
+
+use crate::changes::decode;
+
+#[proxy(interface = "org.bluez.MediaPlayer1", default_service = "org.bluez")]
+trait MediaPlayer1 {
+	/// FastForward method
+	fn fast_forward(&self) -> zbus::Result<()>;
+
+	/// Next method
+	fn next(&self) -> zbus::Result<()>;
+
+	/// Pause method
+	fn pause(&self) -> zbus::Result<()>;
+
+	/// Play method
+	fn play(&self) -> zbus::Result<()>;
+
+	/// Previous method
+	fn previous(&self) -> zbus::Result<()>;
+
+	/// Rewind method
+	fn rewind(&self) -> zbus::Result<()>;
+
+	/// Stop method
+	fn stop(&self) -> zbus::Result<()>;
+
+	/// Device property
+	#[zbus(property)]
+	fn device(&self) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+
+	/// Equalizer property
+	#[zbus(property)]
+	fn equalizer(&self) -> zbus::Result<String>;
+	#[zbus(property)]
+	fn set_equalizer(&self, value: &str) -> zbus::Result<()>;
+
+	/// Name property
+	#[zbus(property)]
+	fn name(&self) -> zbus::Result<String>;
+
+	/// Position property
+	#[zbus(property)]
+	fn position(&self) -> zbus::Result<u32>;
+
+	/// Repeat property
+	#[zbus(property)]
+	fn repeat(&self) -> zbus::Result<String>;
+	#[zbus(property)]
+	fn set_repeat(&self, value: &str) -> zbus::Result<()>;
+
+	/// Shuffle property
+	#[zbus(property)]
+	fn shuffle(&self) -> zbus::Result<String>;
+	#[zbus(property)]
+	fn set_shuffle(&self, value: &str) -> zbus::Result<()>;
+
+	/// Status property
+	#[zbus(property)]
+	fn status(&self) -> zbus::Result<String>;
+
+	/// Subtype property
+	#[zbus(property)]
+	fn subtype(&self) -> zbus::Result<String>;
+
+	/// Track property
+	#[zbus(property)]
+	fn track(&self) -> zbus::Result<Track>;
+
+	/// Type property
+	#[zbus(property, name = "Type")]
+	fn type_(&self) -> zbus::Result<String>;
+}
+
+/// Metadata of the track a media player is playing.
+///
+/// Every field is optional, since players only report what they know.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Track {
+	pub title: Option<String>,
+	pub artist: Option<String>,
+	pub album: Option<String>,
+	pub genre: Option<String>,
+	pub number_of_tracks: Option<u32>,
+	pub track_number: Option<u32>,
+	/// Duration of the track in milliseconds.
+	pub duration: Option<u32>,
+}
+
+impl TryFrom<OwnedValue> for Track {
+	type Error = zbus::zvariant::Error;
+
+	fn try_from(value: OwnedValue) -> Result<Self, Self::Error> {
+		let track = HashMap::<String, OwnedValue>::try_from(value)?;
+		let field = |name: &str| track.get(name);
+
+		Ok(Track {
+			title: field("Title").and_then(|value| decode(value)),
+			artist: field("Artist").and_then(|value| decode(value)),
+			album: field("Album").and_then(|value| decode(value)),
+			genre: field("Genre").and_then(|value| decode(value)),
+			number_of_tracks: field("NumberOfTracks").and_then(|value| decode(value)),
+			track_number: field("TrackNumber").and_then(|value| decode(value)),
+			duration: field("Duration").and_then(|value| decode(value)),
+		})
+	}
+}
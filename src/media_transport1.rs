@@ -0,0 +1,134 @@
+//! # D-Bus interface proxy for: `org.bluez.MediaTransport1`
+//!
+//! This code was generated by `zbus-xmlgen` `4.1.0` from D-Bus introspection data.
+//! Source: `Interface '/org/bluez/hci0/dev_14_3F_A6_A8_16_68/sep1/fd0' from service 'org.bluez' on system bus`.
+//!
+//! You may prefer to adapt it, instead of using it verbatim.
+//!
+//! More information can be found in the [Writing a client proxy] section of the zbus
+//! documentation.
+//!
+//! This type implements the [D-Bus standard interfaces], (`org.freedesktop.DBus.*`) for which the
+//! following zbus API can be used:
+//!
+//! * [`zbus::fdo::IntrospectableProxy`]
+//! * [`zbus::fdo::PropertiesProxy`]
+//!
+//! Consequently `zbus-xmlgen` did not generate code for the above interfaces.
+//!
+//! [Writing a client proxy]: https://dbus2.github.io/zbus/client.html
+//! [D-Bus standard interfaces]: https://dbus.freedesktop.org/doc/dbus-specification.html#standard-interfaces,
+use std::os::fd::OwnedFd;
+
+use zbus::proxy;
+
+#[proxy(interface = "org.bluez.MediaTransport1", default_service = "org.bluez")]
+trait MediaTransport1 {
+	/// Acquire method
+	fn acquire(&self) -> zbus::Result<(zbus::zvariant::OwnedFd, u16, u16)>;
+
+	/// Release method
+	fn release(&self) -> zbus::Result<()>;
+
+	/// TryAcquire method
+	fn try_acquire(&self) -> zbus::Result<(zbus::zvariant::OwnedFd, u16, u16)>;
+
+	/// Codec property
+	#[zbus(property)]
+	fn codec(&self) -> zbus::Result<u8>;
+
+	/// Configuration property
+	#[zbus(property)]
+	fn configuration(&self) -> zbus::Result<Vec<u8>>;
+
+	/// Delay property
+	#[zbus(property)]
+	fn delay(&self) -> zbus::Result<u16>;
+
+	/// Device property
+	#[zbus(property)]
+	fn device(&self) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+
+	/// Endpoint property
+	#[zbus(property)]
+	fn endpoint(&self) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+
+	/// State property
+	#[zbus(property)]
+	fn state(&self) -> zbus::Result<State>;
+
+	/// UUID property
+	#[zbus(property, name = "UUID")]
+	fn uuid(&self) -> zbus::Result<String>;
+
+	/// Volume property
+	#[zbus(property)]
+	fn volume(&self) -> zbus::Result<u16>;
+	#[zbus(property)]
+	fn set_volume(&self, value: u16) -> zbus::Result<()>;
+}
+
+/// State of a media transport.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum State {
+	/// Not streaming.
+	Idle,
+	/// Streaming, but not acquired.
+	Pending,
+	/// Streaming and acquired.
+	Active,
+}
+
+impl TryFrom<&str> for State {
+	type Error = zbus::zvariant::Error;
+
+	fn try_from(state: &str) -> Result<Self, Self::Error> {
+		match state {
+			"idle" => Ok(State::Idle),
+			"pending" => Ok(State::Pending),
+			"active" => Ok(State::Active),
+			_ => Err(zbus::zvariant::Error::Message(format!(
+				"unknown transport state: {state}"
+			))),
+		}
+	}
+}
+
+impl TryFrom<zbus::zvariant::OwnedValue> for State {
+	type Error = zbus::zvariant::Error;
+
+	fn try_from(value: zbus::zvariant::OwnedValue) -> Result<Self, Self::Error> {
+		State::try_from(String::try_from(value)?.as_str())
+	}
+}
+
+/// An acquired transport socket with its MTUs.
+#[derive(Debug)]
+pub struct Acquired {
+	pub fd: OwnedFd,
+	pub read_mtu: u16,
+	pub write_mtu: u16,
+}
+
+impl MediaTransport1Proxy<'_> {
+	/// Acquires the transport socket, as [`Self::acquire`] but with a std `OwnedFd`.
+	pub async fn acquire_fd(&self) -> zbus::Result<Acquired> {
+		let (fd, read_mtu, write_mtu) = self.acquire().await?;
+		Ok(Acquired {
+			fd: OwnedFd::from(fd),
+			read_mtu,
+			write_mtu,
+		})
+	}
+
+	/// Acquires the transport socket only if the transport is `pending`, as
+	/// [`Self::try_acquire`] but with a std `OwnedFd`.
+	pub async fn try_acquire_fd(&self) -> zbus::Result<Acquired> {
+		let (fd, read_mtu, write_mtu) = self.try_acquire().await?;
+		Ok(Acquired {
+			fd: OwnedFd::from(fd),
+			read_mtu,
+			write_mtu,
+		})
+	}
+}
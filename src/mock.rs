@@ -0,0 +1,617 @@
+//! In-memory stand-in for `bluetoothd`, for testing code built on this crate
+//! without a controller or a system bus.
+//!
+//! [`MockBluez`] serves fake `org.bluez.Adapter1` and `org.bluez.Device1`
+//! objects under an `org.freedesktop.DBus.ObjectManager` on one end of a
+//! peer-to-peer connection. The other end, [`MockBluez::connection`], can be
+//! passed wherever a system bus connection is expected, so the regular
+//! [`BluetoothAdapter`](crate::BluetoothAdapter) and
+//! [`BluetoothDevice`](crate::BluetoothDevice) wrappers work unchanged.
+//!
+//! The `set_*` methods of [`MockAdapter`] and [`MockDevice`] change a property
+//! the way BlueZ would, emitting `PropertiesChanged` so that change streams
+//! observe the update.
+//!
+//! Properties that BlueZ sometimes leaves out, such as `Name` and `RSSI`, are
+//! always present on the fake objects, since a failing getter would make
+//! `GetAll` and `GetManagedObjects` fail as a whole.
+//!
+//! Only available with the `mock` feature.
+
+use std::collections::HashMap;
+
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue};
+
+/// Errors returned by the fake objects, in the `org.bluez.Error` namespace.
+#[derive(Debug, zbus::DBusError)]
+#[zbus(prefix = "org.bluez.Error")]
+pub enum MockError {
+    #[zbus(error)]
+    ZBus(zbus::Error),
+    AlreadyConnected(String),
+    AlreadyExists(String),
+    DoesNotExist(String),
+    Failed(String),
+    NotConnected(String),
+    NotReady(String),
+}
+
+/// A fake BlueZ daemon and the connection to reach it.
+pub struct MockBluez {
+    client: zbus::Connection,
+    server: zbus::Connection,
+}
+
+impl MockBluez {
+    pub async fn new() -> zbus::Result<Self> {
+        let (client_socket, server_socket) = std::os::unix::net::UnixStream::pair()
+            .map_err(|why| zbus::Error::InputOutput(why.into()))?;
+
+        let guid = zbus::Guid::generate();
+        let (server, client) = futures_util::try_join!(
+            zbus::connection::Builder::unix_stream(server_socket)
+                .server(guid)?
+                .p2p()
+                .build(),
+            zbus::connection::Builder::unix_stream(client_socket)
+                .p2p()
+                .build(),
+        )?;
+
+        server
+            .object_server()
+            .at("/", zbus::fdo::ObjectManager)
+            .await?;
+
+        Ok(Self { client, server })
+    }
+
+    /// Connection on which the fake objects are reachable.
+    pub fn connection(&self) -> &zbus::Connection {
+        &self.client
+    }
+
+    /// Exports a powered off adapter at `/org/bluez/<name>`.
+    pub async fn add_adapter(&self, name: &str, address: &str) -> zbus::Result<MockAdapter> {
+        let path = OwnedObjectPath::try_from(format!("/org/bluez/{name}"))?;
+
+        self.server
+            .object_server()
+            .at(
+                path.as_str(),
+                AdapterInterface {
+                    address: address.to_owned(),
+                    name: name.to_owned(),
+                    alias: name.to_owned(),
+                    powered: false,
+                    discoverable: false,
+                    discoverable_timeout: 180,
+                    pairable: false,
+                    pairable_timeout: 0,
+                    discovering: false,
+                    uuids: Vec::new(),
+                },
+            )
+            .await?;
+
+        Ok(MockAdapter {
+            server: self.server.clone(),
+            path,
+        })
+    }
+}
+
+/// Handle to a fake adapter exported by [`MockBluez`].
+#[derive(Clone, Debug)]
+pub struct MockAdapter {
+    server: zbus::Connection,
+    path: OwnedObjectPath,
+}
+
+impl MockAdapter {
+    pub fn path(&self) -> ObjectPath<'_> {
+        self.path.as_ref()
+    }
+
+    /// Exports a device below the adapter, as if it had just been discovered.
+    ///
+    /// A device without a `name` reports its alias, the address with dashes,
+    /// as its name. It starts with an RSSI of -60 dBm.
+    pub async fn add_device(&self, address: &str, name: Option<&str>) -> zbus::Result<MockDevice> {
+        let path = crate::device_path(&self.path, address)
+            .ok_or_else(|| zbus::Error::Failure(format!("Invalid device address {address}")))?;
+        let alias = name.map_or_else(|| address.replace(':', "-"), str::to_owned);
+
+        self.server
+            .object_server()
+            .at(
+                path.as_str(),
+                DeviceInterface {
+                    adapter: self.path.clone(),
+                    address: address.to_owned(),
+                    alias: alias.clone(),
+                    name: alias,
+                    blocked: false,
+                    connected: false,
+                    paired: false,
+                    rssi: -60,
+                    services_resolved: false,
+                    trusted: false,
+                    uuids: Vec::new(),
+                },
+            )
+            .await?;
+
+        Ok(MockDevice {
+            server: self.server.clone(),
+            path,
+        })
+    }
+
+    pub async fn set_powered(&self, powered: bool) -> zbus::Result<()> {
+        let interface = self.interface().await?;
+        let mut adapter = interface.get_mut().await;
+        adapter.powered = powered;
+        adapter.powered_changed(interface.signal_context()).await
+    }
+
+    pub async fn set_discovering(&self, discovering: bool) -> zbus::Result<()> {
+        let interface = self.interface().await?;
+        let mut adapter = interface.get_mut().await;
+        adapter.discovering = discovering;
+        adapter
+            .discovering_changed(interface.signal_context())
+            .await
+    }
+
+    pub async fn set_uuids(&self, uuids: Vec<String>) -> zbus::Result<()> {
+        let interface = self.interface().await?;
+        let mut adapter = interface.get_mut().await;
+        adapter.uuids = uuids;
+        adapter.u_u_i_ds_changed(interface.signal_context()).await
+    }
+
+    /// Removes the adapter from the bus, as if it had been unplugged.
+    pub async fn remove(self) -> zbus::Result<()> {
+        self.server
+            .object_server()
+            .remove::<AdapterInterface, _>(self.path.as_str())
+            .await?;
+        Ok(())
+    }
+
+    async fn interface(&self) -> zbus::Result<zbus::InterfaceRef<AdapterInterface>> {
+        self.server
+            .object_server()
+            .interface::<_, AdapterInterface>(self.path.as_str())
+            .await
+    }
+}
+
+/// Handle to a fake device exported by [`MockBluez`].
+#[derive(Clone, Debug)]
+pub struct MockDevice {
+    server: zbus::Connection,
+    path: OwnedObjectPath,
+}
+
+impl MockDevice {
+    pub fn path(&self) -> ObjectPath<'_> {
+        self.path.as_ref()
+    }
+
+    pub async fn set_connected(&self, connected: bool) -> zbus::Result<()> {
+        let interface = self.interface().await?;
+        let mut device = interface.get_mut().await;
+        device.connected = connected;
+        device.connected_changed(interface.signal_context()).await
+    }
+
+    pub async fn set_paired(&self, paired: bool) -> zbus::Result<()> {
+        let interface = self.interface().await?;
+        let mut device = interface.get_mut().await;
+        device.paired = paired;
+        device.paired_changed(interface.signal_context()).await
+    }
+
+    pub async fn set_services_resolved(&self, services_resolved: bool) -> zbus::Result<()> {
+        let interface = self.interface().await?;
+        let mut device = interface.get_mut().await;
+        device.services_resolved = services_resolved;
+        device
+            .services_resolved_changed(interface.signal_context())
+            .await
+    }
+
+    /// Sets the signal strength.
+    pub async fn set_rssi(&self, rssi: i16) -> zbus::Result<()> {
+        let interface = self.interface().await?;
+        let mut device = interface.get_mut().await;
+        device.rssi = rssi;
+        device.r_s_s_i_changed(interface.signal_context()).await
+    }
+
+    pub async fn set_name(&self, name: &str) -> zbus::Result<()> {
+        let interface = self.interface().await?;
+        let mut device = interface.get_mut().await;
+        device.name = name.to_owned();
+        device.name_changed(interface.signal_context()).await
+    }
+
+    pub async fn set_uuids(&self, uuids: Vec<String>) -> zbus::Result<()> {
+        let interface = self.interface().await?;
+        let mut device = interface.get_mut().await;
+        device.uuids = uuids;
+        device.u_u_i_ds_changed(interface.signal_context()).await
+    }
+
+    /// Removes the device from the bus, as if BlueZ had expired it.
+    pub async fn remove(self) -> zbus::Result<()> {
+        self.server
+            .object_server()
+            .remove::<DeviceInterface, _>(self.path.as_str())
+            .await?;
+        Ok(())
+    }
+
+    async fn interface(&self) -> zbus::Result<zbus::InterfaceRef<DeviceInterface>> {
+        self.server
+            .object_server()
+            .interface::<_, DeviceInterface>(self.path.as_str())
+            .await
+    }
+}
+
+struct AdapterInterface {
+    address: String,
+    name: String,
+    alias: String,
+    powered: bool,
+    discoverable: bool,
+    discoverable_timeout: u32,
+    pairable: bool,
+    pairable_timeout: u32,
+    discovering: bool,
+    uuids: Vec<String>,
+}
+
+#[zbus::interface(name = "org.bluez.Adapter1")]
+impl AdapterInterface {
+    async fn start_discovery(
+        &mut self,
+        #[zbus(signal_context)] context: zbus::SignalContext<'_>,
+    ) -> Result<(), MockError> {
+        if !self.powered {
+            return Err(MockError::NotReady("Resource Not Ready".to_owned()));
+        }
+
+        self.discovering = true;
+        self.discovering_changed(&context).await?;
+        Ok(())
+    }
+
+    async fn stop_discovery(
+        &mut self,
+        #[zbus(signal_context)] context: zbus::SignalContext<'_>,
+    ) -> Result<(), MockError> {
+        if !self.discovering {
+            return Err(MockError::Failed("No discovery started".to_owned()));
+        }
+
+        self.discovering = false;
+        self.discovering_changed(&context).await?;
+        Ok(())
+    }
+
+    async fn set_discovery_filter(&self, _properties: HashMap<String, OwnedValue>) {}
+
+    async fn get_discovery_filters(&self) -> Vec<String> {
+        [
+            "UUIDs",
+            "RSSI",
+            "Pathloss",
+            "Transport",
+            "DuplicateData",
+            "Discoverable",
+            "Pattern",
+        ]
+        .into_iter()
+        .map(str::to_owned)
+        .collect()
+    }
+
+    async fn remove_device(
+        &self,
+        device: ObjectPath<'_>,
+        #[zbus(connection)] connection: &zbus::Connection,
+    ) -> Result<(), MockError> {
+        let removed = connection
+            .object_server()
+            .remove::<DeviceInterface, _>(&device)
+            .await
+            .unwrap_or(false);
+
+        if !removed {
+            return Err(MockError::DoesNotExist("Does Not Exist".to_owned()));
+        }
+
+        Ok(())
+    }
+
+    #[zbus(property)]
+    fn address(&self) -> String {
+        self.address.clone()
+    }
+
+    #[zbus(property)]
+    fn address_type(&self) -> String {
+        "public".to_owned()
+    }
+
+    #[zbus(property)]
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    #[zbus(property)]
+    fn alias(&self) -> String {
+        self.alias.clone()
+    }
+
+    #[zbus(property)]
+    fn set_alias(&mut self, alias: String) {
+        self.alias = alias;
+    }
+
+    #[zbus(property)]
+    fn class(&self) -> u32 {
+        0
+    }
+
+    #[zbus(property)]
+    fn powered(&self) -> bool {
+        self.powered
+    }
+
+    #[zbus(property)]
+    fn set_powered(&mut self, powered: bool) {
+        self.powered = powered;
+    }
+
+    #[zbus(property)]
+    fn discoverable(&self) -> bool {
+        self.discoverable
+    }
+
+    #[zbus(property)]
+    fn set_discoverable(&mut self, discoverable: bool) {
+        self.discoverable = discoverable;
+    }
+
+    #[zbus(property)]
+    fn discoverable_timeout(&self) -> u32 {
+        self.discoverable_timeout
+    }
+
+    #[zbus(property)]
+    fn set_discoverable_timeout(&mut self, timeout: u32) {
+        self.discoverable_timeout = timeout;
+    }
+
+    #[zbus(property)]
+    fn pairable(&self) -> bool {
+        self.pairable
+    }
+
+    #[zbus(property)]
+    fn set_pairable(&mut self, pairable: bool) {
+        self.pairable = pairable;
+    }
+
+    #[zbus(property)]
+    fn pairable_timeout(&self) -> u32 {
+        self.pairable_timeout
+    }
+
+    #[zbus(property)]
+    fn set_pairable_timeout(&mut self, timeout: u32) {
+        self.pairable_timeout = timeout;
+    }
+
+    #[zbus(property)]
+    fn discovering(&self) -> bool {
+        self.discovering
+    }
+
+    #[zbus(property, name = "UUIDs")]
+    fn uuids(&self) -> Vec<String> {
+        self.uuids.clone()
+    }
+
+    #[zbus(property)]
+    fn roles(&self) -> Vec<String> {
+        vec!["central".to_owned(), "peripheral".to_owned()]
+    }
+
+    #[zbus(property)]
+    fn experimental_features(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+struct DeviceInterface {
+    adapter: OwnedObjectPath,
+    address: String,
+    alias: String,
+    name: String,
+    blocked: bool,
+    connected: bool,
+    paired: bool,
+    rssi: i16,
+    services_resolved: bool,
+    trusted: bool,
+    uuids: Vec<String>,
+}
+
+impl DeviceInterface {
+    async fn set_link(
+        &mut self,
+        connected: bool,
+        context: &zbus::SignalContext<'_>,
+    ) -> zbus::Result<()> {
+        self.connected = connected;
+        self.services_resolved = connected;
+        self.connected_changed(context).await?;
+        self.services_resolved_changed(context).await
+    }
+}
+
+#[zbus::interface(name = "org.bluez.Device1")]
+impl DeviceInterface {
+    /// Connects the device and resolves its services immediately.
+    async fn connect(
+        &mut self,
+        #[zbus(signal_context)] context: zbus::SignalContext<'_>,
+    ) -> Result<(), MockError> {
+        if self.connected {
+            return Err(MockError::AlreadyConnected("Already Connected".to_owned()));
+        }
+
+        self.set_link(true, &context).await?;
+        Ok(())
+    }
+
+    async fn disconnect(
+        &mut self,
+        #[zbus(signal_context)] context: zbus::SignalContext<'_>,
+    ) -> Result<(), MockError> {
+        if !self.connected {
+            return Err(MockError::NotConnected("Not Connected".to_owned()));
+        }
+
+        self.set_link(false, &context).await?;
+        Ok(())
+    }
+
+    async fn connect_profile(
+        &mut self,
+        _uuid: String,
+        #[zbus(signal_context)] context: zbus::SignalContext<'_>,
+    ) -> Result<(), MockError> {
+        if !self.connected {
+            self.set_link(true, &context).await?;
+        }
+        Ok(())
+    }
+
+    async fn disconnect_profile(
+        &mut self,
+        _uuid: String,
+        #[zbus(signal_context)] context: zbus::SignalContext<'_>,
+    ) -> Result<(), MockError> {
+        if !self.connected {
+            return Err(MockError::NotConnected("Not Connected".to_owned()));
+        }
+
+        self.set_link(false, &context).await?;
+        Ok(())
+    }
+
+    /// Pairs without involving an agent.
+    async fn pair(
+        &mut self,
+        #[zbus(signal_context)] context: zbus::SignalContext<'_>,
+    ) -> Result<(), MockError> {
+        if self.paired {
+            return Err(MockError::AlreadyExists("Already Exists".to_owned()));
+        }
+
+        self.paired = true;
+        self.paired_changed(&context).await?;
+        Ok(())
+    }
+
+    async fn cancel_pairing(&self) -> Result<(), MockError> {
+        Err(MockError::DoesNotExist("Does Not Exist".to_owned()))
+    }
+
+    #[zbus(property)]
+    fn adapter(&self) -> OwnedObjectPath {
+        self.adapter.clone()
+    }
+
+    #[zbus(property)]
+    fn address(&self) -> String {
+        self.address.clone()
+    }
+
+    #[zbus(property)]
+    fn address_type(&self) -> String {
+        "public".to_owned()
+    }
+
+    #[zbus(property)]
+    fn alias(&self) -> String {
+        self.alias.clone()
+    }
+
+    #[zbus(property)]
+    fn set_alias(&mut self, alias: String) {
+        self.alias = alias;
+    }
+
+    #[zbus(property)]
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    #[zbus(property)]
+    fn blocked(&self) -> bool {
+        self.blocked
+    }
+
+    #[zbus(property)]
+    fn set_blocked(&mut self, blocked: bool) {
+        self.blocked = blocked;
+    }
+
+    #[zbus(property)]
+    fn connected(&self) -> bool {
+        self.connected
+    }
+
+    #[zbus(property)]
+    fn legacy_pairing(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn paired(&self) -> bool {
+        self.paired
+    }
+
+    #[zbus(property, name = "RSSI")]
+    fn rssi(&self) -> i16 {
+        self.rssi
+    }
+
+    #[zbus(property)]
+    fn services_resolved(&self) -> bool {
+        self.services_resolved
+    }
+
+    #[zbus(property)]
+    fn trusted(&self) -> bool {
+        self.trusted
+    }
+
+    #[zbus(property)]
+    fn set_trusted(&mut self, trusted: bool) {
+        self.trusted = trusted;
+    }
+
+    #[zbus(property, name = "UUIDs")]
+    fn uuids(&self) -> Vec<String> {
+        self.uuids.clone()
+    }
+}
@@ -0,0 +1,65 @@
+//! Parsing of the `Modalias` property, as found in the Device ID profile.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A device modalias such as `usb:v045Ep0040d0100`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Modalias {
+	/// Who assigned the vendor identifier, such as `usb` or `bluetooth`.
+	pub source: String,
+	pub vendor: u16,
+	pub product: u16,
+	pub version: u16,
+}
+
+impl FromStr for Modalias {
+	type Err = InvalidModalias;
+
+	fn from_str(modalias: &str) -> Result<Self, Self::Err> {
+		let invalid = || InvalidModalias(modalias.to_owned());
+
+		let (source, ids) = modalias.split_once(':').ok_or_else(invalid)?;
+		let field = |prefix: char, index: usize| -> Result<u16, InvalidModalias> {
+			let field = ids.get(index * 5..index * 5 + 5).ok_or_else(invalid)?;
+			let hex = field.strip_prefix(prefix).ok_or_else(invalid)?;
+			if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+				return Err(invalid());
+			}
+			u16::from_str_radix(hex, 16).map_err(|_| invalid())
+		};
+
+		if source.is_empty() || ids.len() != 15 {
+			return Err(invalid());
+		}
+
+		Ok(Self {
+			source: source.to_owned(),
+			vendor: field('v', 0)?,
+			product: field('p', 1)?,
+			version: field('d', 2)?,
+		})
+	}
+}
+
+impl fmt::Display for Modalias {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{}:v{:04X}p{:04X}d{:04X}",
+			self.source, self.vendor, self.product, self.version
+		)
+	}
+}
+
+/// Error returned when parsing a string that is not a modalias.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidModalias(pub String);
+
+impl fmt::Display for InvalidModalias {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "invalid modalias: {}", self.0)
+	}
+}
+
+impl std::error::Error for InvalidModalias {}
@@ -0,0 +1,43 @@
+#![allow(non_snake_case)]
+
+//! # D-Bus interface proxy for: `org.bluez.Network1`
+//!
+//! This code was generated by `zbus-xmlgen` `4.1.0` from D-Bus introspection data.
+//! Source: `Interface '/org/bluez/hci0/dev_14_3F_A6_A8_16_68' from service 'org.bluez' on system bus`.
+//!
+//! You may prefer to adapt it, instead of using it verbatim.
+//!
+//! More information can be found in the [Writing a client proxy] section of the zbus
+//! documentation.
+//!
+//! This type implements the [D-Bus standard interfaces], (`org.freedesktop.DBus.*`) for which the
+//! following zbus API can be used:
+//!
+//! * [`zbus::fdo::IntrospectableProxy`]
+//! * [`zbus::fdo::PropertiesProxy`]
+//!
+//! Consequently `zbus-xmlgen` did not generate code for the above interfaces.
+//!
+//! [Writing a client proxy]: https://dbus2.github.io/zbus/client.html
+//! [D-Bus standard interfaces]: https://dbus.freedesktop.org/doc/dbus-specification.html#standard-interfaces,
+use zbus::proxy;
+#[proxy(interface = "org.bluez.Network1", default_service = "org.bluez")]
+trait Network1 {
+	/// Connect method
+	fn connect(&self, UUID: &str) -> zbus::Result<String>;
+
+	/// Disconnect method
+	fn disconnect(&self) -> zbus::Result<()>;
+
+	/// Connected property
+	#[zbus(property)]
+	fn connected(&self) -> zbus::Result<bool>;
+
+	/// Interface property
+	#[zbus(property)]
+	fn interface(&self) -> zbus::Result<String>;
+
+	/// UUID property
+	#[zbus(property, name = "UUID")]
+	fn uuid(&self) -> zbus::Result<String>;
+}
@@ -0,0 +1,98 @@
+//! Bindings for `org.bluez.obex`, the OBEX service `obexd` provides on the session bus.
+
+pub mod client1;
+pub mod object_push1;
+pub mod phonebook_access1;
+pub mod session1;
+pub mod transfer1;
+
+use std::collections::HashMap;
+
+use zbus::zvariant::{ObjectPath, Value};
+
+static SESSION_CONNECTION: tokio::sync::OnceCell<zbus::Connection> =
+	tokio::sync::OnceCell::const_new();
+
+/// Connection to the session bus shared by the OBEX helpers, opened on first use.
+pub async fn session_connection() -> zbus::Result<zbus::Connection> {
+	SESSION_CONNECTION
+		.get_or_try_init(zbus::Connection::session)
+		.await
+		.cloned()
+}
+
+/// Creates a session to the device with the address `destination` for the
+/// OBEX service `target`, such as `"opp"` for Object Push or `"pbap"`.
+pub async fn create_session(
+	destination: &str,
+	target: &str,
+) -> zbus::Result<session1::Session1Proxy<'static>> {
+	let connection = session_connection().await?;
+	let client = client1::Client1Proxy::new(&connection).await?;
+	let target = Value::from(target);
+	let session_path = client
+		.create_session(destination, HashMap::from([("Target", &target)]))
+		.await?;
+
+	session1::Session1Proxy::builder(&connection)
+		.path(session_path)?
+		.build()
+		.await
+}
+
+/// Removes a session created with [`create_session`], cancelling its transfers.
+pub async fn remove_session(session: &ObjectPath<'_>) -> zbus::Result<()> {
+	let connection = session_connection().await?;
+	client1::Client1Proxy::new(&connection)
+		.await?
+		.remove_session(session)
+		.await
+}
+
+/// Sends the file at `source_file` to the device with the address `destination`
+/// over Object Push, returning the session and the transfer to follow.
+///
+/// The session has to be removed with [`remove_session`] once the transfer is done.
+pub async fn send_file(
+	destination: &str,
+	source_file: &str,
+) -> zbus::Result<(session1::Session1Proxy<'static>, transfer1::Transfer1Proxy<'static>)> {
+	let session = create_session(destination, "opp").await?;
+	let object_push = object_push1::ObjectPush1Proxy::builder(session.inner().connection())
+		.path(session.inner().path().to_owned())?
+		.build()
+		.await?;
+
+	let transfer_path = match object_push.send_file(source_file).await {
+		Ok((transfer_path, _properties)) => transfer_path,
+		Err(why) => {
+			_ = remove_session(session.inner().path()).await;
+			return Err(why);
+		}
+	};
+
+	let transfer = transfer1::Transfer1Proxy::builder(session.inner().connection())
+		.path(transfer_path)?
+		.build()
+		.await?;
+
+	Ok((session, transfer))
+}
+
+/// Opens a Phonebook Access session to the device with the address `destination`.
+///
+/// The session has to be removed with [`remove_session`] once done.
+pub async fn phonebook_access(
+	destination: &str,
+) -> zbus::Result<(
+	session1::Session1Proxy<'static>,
+	phonebook_access1::PhonebookAccess1Proxy<'static>,
+)> {
+	let session = create_session(destination, "pbap").await?;
+	let phonebook = phonebook_access1::PhonebookAccess1Proxy::builder(session.inner().connection())
+		.path(session.inner().path().to_owned())?
+		.build()
+		.await?;
+
+	Ok((session, phonebook))
+}
@@ -0,0 +1,51 @@
+//! # D-Bus interface proxy for: `org.bluez.obex.ObjectPush1`
+//!
+//! This code was generated by `zbus-xmlgen` `4.1.0` from D-Bus introspection data.
+//! Source: `Interface '/org/bluez/obex/client/session0' from service 'org.bluez.obex' on session bus`.
+//!
+//! You may prefer to adapt it, instead of using it verbatim.
+//!
+//! More information can be found in the [Writing a client proxy] section of the zbus
+//! documentation.
+//!
+//! This type implements the [D-Bus standard interfaces], (`org.freedesktop.DBus.*`) for which the
+//! following zbus API can be used:
+//!
+//! * [`zbus::fdo::IntrospectableProxy`]
+//! * [`zbus::fdo::PropertiesProxy`]
+//!
+//! Consequently `zbus-xmlgen` did not generate code for the above interfaces.
+//!
+//! [Writing a client proxy]: https://dbus2.github.io/zbus/client.html
+//! [D-Bus standard interfaces]: https://dbus.freedesktop.org/doc/dbus-specification.html#standard-interfaces,
+use zbus::proxy;
+#[proxy(interface = "org.bluez.obex.ObjectPush1", default_service = "org.bluez.obex")]
+trait ObjectPush1 {
+	/// ExchangeBusinessCards method
+	fn exchange_business_cards(
+		&self,
+		clientfile: &str,
+		targetfile: &str,
+	) -> zbus::Result<(
+		zbus::zvariant::OwnedObjectPath,
+		std::collections::HashMap<String, zbus::zvariant::OwnedValue>,
+	)>;
+
+	/// PullBusinessCard method
+	fn pull_business_card(
+		&self,
+		targetfile: &str,
+	) -> zbus::Result<(
+		zbus::zvariant::OwnedObjectPath,
+		std::collections::HashMap<String, zbus::zvariant::OwnedValue>,
+	)>;
+
+	/// SendFile method
+	fn send_file(
+		&self,
+		sourcefile: &str,
+	) -> zbus::Result<(
+		zbus::zvariant::OwnedObjectPath,
+		std::collections::HashMap<String, zbus::zvariant::OwnedValue>,
+	)>;
+}
@@ -0,0 +1,46 @@
+//! # D-Bus interface proxy for: `org.bluez.obex.Session1`
+//!
+//! This code was generated by `zbus-xmlgen` `4.1.0` from D-Bus introspection data.
+//! Source: `Interface '/org/bluez/obex/client/session0' from service 'org.bluez.obex' on session bus`.
+//!
+//! You may prefer to adapt it, instead of using it verbatim.
+//!
+//! More information can be found in the [Writing a client proxy] section of the zbus
+//! documentation.
+//!
+//! This type implements the [D-Bus standard interfaces], (`org.freedesktop.DBus.*`) for which the
+//! following zbus API can be used:
+//!
+//! * [`zbus::fdo::IntrospectableProxy`]
+//! * [`zbus::fdo::PropertiesProxy`]
+//!
+//! Consequently `zbus-xmlgen` did not generate code for the above interfaces.
+//!
+//! [Writing a client proxy]: https://dbus2.github.io/zbus/client.html
+//! [D-Bus standard interfaces]: https://dbus.freedesktop.org/doc/dbus-specification.html#standard-interfaces,
+use zbus::proxy;
+#[proxy(interface = "org.bluez.obex.Session1", default_service = "org.bluez.obex")]
+trait Session1 {
+	/// GetCapabilities method
+	fn get_capabilities(&self) -> zbus::Result<String>;
+
+	/// Channel property
+	#[zbus(property)]
+	fn channel(&self) -> zbus::Result<u8>;
+
+	/// Destination property
+	#[zbus(property)]
+	fn destination(&self) -> zbus::Result<String>;
+
+	/// Root property
+	#[zbus(property)]
+	fn root(&self) -> zbus::Result<String>;
+
+	/// Source property
+	#[zbus(property)]
+	fn source(&self) -> zbus::Result<String>;
+
+	/// Target property
+	#[zbus(property)]
+	fn target(&self) -> zbus::Result<String>;
+}
@@ -0,0 +1,169 @@
+//! # D-Bus interface proxy for: `org.bluez.obex.Transfer1`
+//!
+//! This code was generated by `zbus-xmlgen` `4.1.0` from D-Bus introspection data.
+//! Source: `Interface '/org/bluez/obex/client/session0/transfer0' from service 'org.bluez.obex' on session bus`.
+//!
+//! You may prefer to adapt it, instead of using it verbatim.
+//!
+//! More information can be found in the [Writing a client proxy] section of the zbus
+//! documentation.
+//!
+//! This type implements the [D-Bus standard interfaces], (`org.freedesktop.DBus.*`) for which the
+//! following zbus API can be used:
+//!
+//! * [`zbus::fdo::IntrospectableProxy`]
+//! * [`zbus::fdo::PropertiesProxy`]
+//!
+//! Consequently `zbus-xmlgen` did not generate code for the above interfaces.
+//!
+//! [Writing a client proxy]: https://dbus2.github.io/zbus/client.html
+//! [D-Bus standard interfaces]: https://dbus.freedesktop.org/doc/dbus-specification.html#standard-interfaces,
+use zbus::proxy;
+#[proxy(interface = "org.bluez.obex.Transfer1", default_service = "org.bluez.obex")]
+trait Transfer1 {
+	/// Cancel method
+	fn cancel(&self) -> zbus::Result<()>;
+
+	/// Resume method
+	fn resume(&self) -> zbus::Result<()>;
+
+	/// Suspend method
+	fn suspend(&self) -> zbus::Result<()>;
+
+	/// Filename property
+	#[zbus(property)]
+	fn filename(&self) -> zbus::Result<String>;
+
+	/// Name property
+	#[zbus(property)]
+	fn name(&self) -> zbus::Result<String>;
+
+	/// Session property
+	#[zbus(property)]
+	fn session(&self) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+
+	/// Size property
+	#[zbus(property)]
+	fn size(&self) -> zbus::Result<u64>;
+
+	/// Status property
+	#[zbus(property)]
+	fn status(&self) -> zbus::Result<String>;
+
+	/// Time property
+	#[zbus(property)]
+	fn time(&self) -> zbus::Result<u64>;
+
+	/// Transferred property
+	#[zbus(property)]
+	fn transferred(&self) -> zbus::Result<u64>;
+
+	/// Type property
+	#[zbus(property, name = "Type")]
+	fn type_(&self) -> zbus::Result<String>;
+}
+
+impl<'p> Transfer1Proxy<'p> {
+	/// Stream of the number of bytes transferred so far, starting with the current count.
+	pub async fn progress(&self) -> impl futures_util::Stream<Item = u64> + 'p {
+		use crate::changes::PropertyStreamExt;
+
+		self.receive_transferred_changed().await.values()
+	}
+
+	/// Waits until the transfer is complete, failing if it errors out.
+	///
+	/// A transfer that `obexd` removes, or that disappears because `obexd`
+	/// exits, before reporting `complete` or `error` is reported as a failure,
+	/// since its status never changes again.
+	pub async fn await_complete(&self) -> Result<(), crate::BluezError> {
+		use futures_util::StreamExt;
+
+		use crate::changes::PropertyStreamExt;
+
+		// Subscribe before reading the status so that a change is not missed.
+		let objects = zbus::fdo::ObjectManagerProxy::builder(self.inner().connection())
+			.destination(self.inner().destination().to_owned())?
+			.path("/")?
+			.build()
+			.await?;
+		let path = self.inner().path().to_owned();
+		let removed = objects
+			.receive_interfaces_removed()
+			.await?
+			.filter(move |signal| {
+				let removed = signal.args().is_ok_and(|args| {
+					*args.object_path() == path
+						&& args.interfaces().contains(&"org.bluez.obex.Transfer1")
+				});
+				async move { removed }
+			})
+			.map(|_| None);
+		let exited = self
+			.inner()
+			.receive_owner_changed()
+			.await?
+			.filter(|owner| std::future::ready(owner.is_none()))
+			.map(|_| None);
+		let changes = self.receive_status_changed().await;
+
+		let status = TransferStatus::try_from(self.status().await?.as_str()).map_err(zbus::Error::from)?;
+		if let Some(result) = finished(status) {
+			return result;
+		}
+
+		let statuses = changes.values().filter_map(|status| async move {
+			TransferStatus::try_from(status.as_str()).ok().map(Some)
+		});
+		let mut events = std::pin::pin!(futures_util::stream::select(
+			statuses,
+			futures_util::stream::select(removed, exited),
+		));
+
+		// `None` marks the transfer or `obexd` going away.
+		while let Some(Some(status)) = events.next().await {
+			if let Some(result) = finished(status) {
+				return result;
+			}
+		}
+
+		Err(crate::BluezError::Failed(
+			"transfer removed before completing".to_owned(),
+		))
+	}
+}
+
+/// State of a transfer, as reported in `Status`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransferStatus {
+	Queued,
+	Active,
+	Suspended,
+	Complete,
+	Error,
+}
+
+impl TryFrom<&str> for TransferStatus {
+	type Error = zbus::zvariant::Error;
+
+	fn try_from(status: &str) -> Result<Self, zbus::zvariant::Error> {
+		match status {
+			"queued" => Ok(TransferStatus::Queued),
+			"active" => Ok(TransferStatus::Active),
+			"suspended" => Ok(TransferStatus::Suspended),
+			"complete" => Ok(TransferStatus::Complete),
+			"error" => Ok(TransferStatus::Error),
+			_ => Err(zbus::zvariant::Error::Message(format!(
+				"unknown transfer status: {status}"
+			))),
+		}
+	}
+}
+
+fn finished(status: TransferStatus) -> Option<Result<(), crate::BluezError>> {
+	match status {
+		TransferStatus::Complete => Some(Ok(())),
+		TransferStatus::Error => Some(Err(crate::BluezError::Failed("transfer failed".to_owned()))),
+		_ => None,
+	}
+}
@@ -0,0 +1,141 @@
+//! Enumeration of the objects BlueZ exports, through its `org.freedesktop.DBus.ObjectManager`.
+
+use std::collections::HashMap;
+
+use futures_util::{Stream, StreamExt};
+use zbus::names::OwnedInterfaceName;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue};
+
+use crate::{BluetoothAdapter, BluetoothDevice};
+
+/// Fetches every object exported by BlueZ with a single `GetManagedObjects` call.
+pub async fn managed_objects(connection: &zbus::Connection) -> zbus::Result<zbus::fdo::ManagedObjects> {
+	let managed_object_proxy =
+		zbus::fdo::ObjectManagerProxy::new(connection, "org.bluez", "/").await?;
+	Ok(managed_object_proxy.get_managed_objects().await?)
+}
+
+/// Paths of the objects implementing `interface`, sorted so that the order is stable.
+pub fn paths_with_interface(
+	managed_object: &zbus::fdo::ManagedObjects,
+	interface: &str,
+) -> Vec<OwnedObjectPath> {
+	let mut paths: Vec<OwnedObjectPath> = managed_object
+		.iter()
+		.filter(|(_, interfaces)| interfaces.contains_key(interface))
+		.map(|(path, _)| path.clone())
+		.collect();
+	paths.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+	paths
+}
+
+pub async fn all_adapters<'a>(connection: &zbus::Connection) -> zbus::Result<Vec<BluetoothAdapter<'a>>> {
+	let managed_object = managed_objects(connection).await?;
+	let adapter_addresses = paths_with_interface(&managed_object, "org.bluez.Adapter1");
+
+	futures_util::future::try_join_all(
+		adapter_addresses
+			.into_iter()
+			.map(|path| BluetoothAdapter::new(connection, path.into())),
+	)
+	.await
+}
+
+pub async fn all_devices<'a>(connection: &zbus::Connection) -> zbus::Result<Vec<BluetoothDevice<'a>>> {
+	let managed_object = managed_objects(connection).await?;
+	let device_addresses = paths_with_interface(&managed_object, "org.bluez.Device1");
+
+	futures_util::future::try_join_all(
+		device_addresses
+			.into_iter()
+			.map(|path| BluetoothDevice::new(connection, path.into())),
+	)
+	.await
+}
+
+/// Devices that are direct children of the adapter at `adapter_path`.
+pub async fn devices_for_adapter<'a>(
+	connection: &zbus::Connection,
+	adapter_path: &ObjectPath<'_>,
+) -> zbus::Result<Vec<BluetoothDevice<'a>>> {
+	let managed_object = managed_objects(connection).await?;
+	let device_addresses = paths_with_interface(&managed_object, "org.bluez.Device1");
+
+	futures_util::future::try_join_all(
+		device_addresses
+			.into_iter()
+			.filter(|path| {
+				path.as_str()
+					.rsplit_once('/')
+					.is_some_and(|(parent, _)| parent == adapter_path.as_str())
+			})
+			.map(|path| BluetoothDevice::new(connection, path.into())),
+	)
+	.await
+}
+
+/// An object appearing on, or disappearing from, the BlueZ object tree.
+#[derive(Debug)]
+pub enum ObjectEvent {
+	/// Interfaces were added to `path`, with the initial value of their properties.
+	Added {
+		path: OwnedObjectPath,
+		interfaces: HashMap<OwnedInterfaceName, HashMap<String, OwnedValue>>,
+	},
+	/// Interfaces were removed from `path`.
+	Removed {
+		path: OwnedObjectPath,
+		interfaces: Vec<OwnedInterfaceName>,
+	},
+}
+
+/// Stream of the `InterfacesAdded` and `InterfacesRemoved` signals of the BlueZ object manager.
+pub async fn watch(connection: &zbus::Connection) -> zbus::Result<impl Stream<Item = ObjectEvent>> {
+	let managed_object_proxy =
+		zbus::fdo::ObjectManagerProxy::new(connection, "org.bluez", "/").await?;
+
+	let added = managed_object_proxy
+		.receive_interfaces_added()
+		.await?
+		.filter_map(|signal| async move {
+			let args = signal.args().ok()?;
+			let interfaces = args
+				.interfaces_and_properties()
+				.iter()
+				.filter_map(|(interface, properties)| {
+					let interface = OwnedInterfaceName::try_from(*interface).ok()?;
+					let properties = properties
+						.iter()
+						.filter_map(|(name, value)| {
+							Some((name.to_string(), value.try_to_owned().ok()?))
+						})
+						.collect();
+					Some((interface, properties))
+				})
+				.collect();
+
+			Some(ObjectEvent::Added {
+				path: args.object_path().to_owned().into(),
+				interfaces,
+			})
+		});
+
+	let removed = managed_object_proxy
+		.receive_interfaces_removed()
+		.await?
+		.filter_map(|signal| async move {
+			let args = signal.args().ok()?;
+			let interfaces = args
+				.interfaces()
+				.iter()
+				.filter_map(|interface| OwnedInterfaceName::try_from(*interface).ok())
+				.collect();
+
+			Some(ObjectEvent::Removed {
+				path: args.object_path().to_owned().into(),
+				interfaces,
+			})
+		});
+
+	Ok(futures_util::stream::select(added, removed))
+}
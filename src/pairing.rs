@@ -0,0 +1,102 @@
+//! High-level pairing helpers.
+
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath};
+
+use crate::{agent_manager1, device1, BluezError};
+
+/// Options for [`pair_device`].
+#[derive(Clone, Debug)]
+pub struct PairOptions {
+	/// How long to wait for pairing to complete before cancelling it.
+	pub timeout: Duration,
+	/// Whether to set `Trusted` on the device once pairing succeeds.
+	pub trust_after_pair: bool,
+}
+
+impl Default for PairOptions {
+	fn default() -> Self {
+		Self {
+			timeout: Duration::from_secs(60),
+			trust_after_pair: false,
+		}
+	}
+}
+
+/// How pairing with a device proceeds, as anticipated from `LegacyPairing`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PairingMethod {
+	/// Pre-2.1 PIN code pairing, which offers no protection against eavesdropping.
+	Legacy,
+	/// Secure Simple Pairing, or LE pairing.
+	SecureSimplePairing,
+}
+
+impl From<bool> for PairingMethod {
+	fn from(legacy_pairing: bool) -> Self {
+		if legacy_pairing {
+			Self::Legacy
+		} else {
+			Self::SecureSimplePairing
+		}
+	}
+}
+
+/// Pairs with the device at `device_path`, using the agent registered at `agent_path`.
+///
+/// The agent is made the default agent so that BlueZ routes the pairing requests
+/// to it. This resolves once the device reports `Paired`, or fails if the agent
+/// rejects or cancels the request. When `options.timeout` elapses first,
+/// `CancelPairing` is called and [`BluezError::Timeout`] is returned.
+///
+/// With `options.trust_after_pair`, the device is also marked as trusted.
+#[tracing::instrument(
+	skip_all,
+	fields(device = %device_path.as_str(), agent = %agent_path.as_str(), ?options)
+)]
+pub async fn pair_device(
+	connection: &zbus::Connection,
+	device_path: OwnedObjectPath,
+	agent_path: &ObjectPath<'_>,
+	options: PairOptions,
+) -> Result<(), BluezError> {
+	let agent_manager = agent_manager1::AgentManager1Proxy::new(connection).await?;
+	agent_manager.request_default_agent(agent_path).await?;
+
+	let device = device1::Device1Proxy::builder(connection)
+		.path(device_path)?
+		.build()
+		.await?;
+	let mut paired_changed = device.receive_paired_changed().await;
+
+	let pairing = async {
+		device.pair().await?;
+
+		// `Pair` returns once bonding is done, but the property may lag behind.
+		if device.paired().await? {
+			return Ok(());
+		}
+		while let Some(change) = paired_changed.next().await {
+			if change.get().await? {
+				return Ok(());
+			}
+		}
+
+		Err(BluezError::Failed("device did not become paired".to_owned()))
+	};
+
+	match tokio::time::timeout(options.timeout, pairing).await {
+		Ok(Ok(())) if options.trust_after_pair => {
+			device.set_trusted(true).await.map_err(BluezError::from)
+		}
+		Ok(result) => result,
+		Err(_) => {
+			if let Err(why) = device.cancel_pairing().await {
+				tracing::warn!(?why, "failed to cancel pairing");
+			}
+			Err(BluezError::Timeout)
+		}
+	}
+}
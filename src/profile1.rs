@@ -0,0 +1,184 @@
+//! Integrations for creating bluez profiles, such as RFCOMM serial ports.
+
+use std::collections::HashMap;
+use std::os::fd::OwnedFd;
+
+use tokio::sync::mpsc;
+use zbus::zvariant::{OwnedObjectPath, OwnedValue, Value};
+
+pub fn create() -> (Profile, mpsc::Receiver<Message>) {
+    let (message_sender, message_receiver) = tokio::sync::mpsc::channel(1);
+
+    (Profile { message_sender }, message_receiver)
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Role {
+    Client,
+    Server,
+}
+
+impl From<Role> for &'static str {
+    fn from(role: Role) -> &'static str {
+        match role {
+            Role::Client => "client",
+            Role::Server => "server",
+        }
+    }
+}
+
+/// Options passed to `org.bluez.ProfileManager1.RegisterProfile`.
+///
+/// Fields left as `None` are not sent, so BlueZ picks its default for them.
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    /// Human readable name for the profile.
+    pub name: Option<String>,
+    /// The primary service class UUID, if different from the profile UUID.
+    pub service: Option<String>,
+    pub role: Option<Role>,
+    /// RFCOMM channel number that is used for client and server UUIDs.
+    pub channel: Option<u16>,
+    /// PSM number that is used for client and server UUIDs.
+    pub psm: Option<u16>,
+    pub require_authentication: Option<bool>,
+    pub require_authorization: Option<bool>,
+    /// Whether BlueZ should connect the profile automatically when possible.
+    pub auto_connect: Option<bool>,
+    /// SDP record in XML format, replacing the one BlueZ would generate.
+    pub service_record: Option<String>,
+    pub version: Option<u16>,
+    pub features: Option<u16>,
+}
+
+impl Options {
+    /// Serializes the options into the `a{sv}` dictionary BlueZ expects.
+    pub fn to_dict(&self) -> HashMap<String, Value<'static>> {
+        let mut dict = HashMap::new();
+
+        if let Some(name) = &self.name {
+            dict.insert("Name".to_owned(), Value::from(name.clone()));
+        }
+        if let Some(service) = &self.service {
+            dict.insert("Service".to_owned(), Value::from(service.clone()));
+        }
+        if let Some(role) = self.role {
+            dict.insert("Role".to_owned(), Value::from(<&'static str>::from(role)));
+        }
+        if let Some(channel) = self.channel {
+            dict.insert("Channel".to_owned(), Value::from(channel));
+        }
+        if let Some(psm) = self.psm {
+            dict.insert("PSM".to_owned(), Value::from(psm));
+        }
+        if let Some(require_authentication) = self.require_authentication {
+            dict.insert(
+                "RequireAuthentication".to_owned(),
+                Value::from(require_authentication),
+            );
+        }
+        if let Some(require_authorization) = self.require_authorization {
+            dict.insert(
+                "RequireAuthorization".to_owned(),
+                Value::from(require_authorization),
+            );
+        }
+        if let Some(auto_connect) = self.auto_connect {
+            dict.insert("AutoConnect".to_owned(), Value::from(auto_connect));
+        }
+        if let Some(service_record) = &self.service_record {
+            dict.insert("ServiceRecord".to_owned(), Value::from(service_record.clone()));
+        }
+        if let Some(version) = self.version {
+            dict.insert("Version".to_owned(), Value::from(version));
+        }
+        if let Some(features) = self.features {
+            dict.insert("Features".to_owned(), Value::from(features));
+        }
+
+        dict
+    }
+}
+
+#[derive(Debug)]
+pub enum Message {
+    NewConnection {
+        device: OwnedObjectPath,
+        /// The connected socket, owned by the receiver of this message.
+        fd: OwnedFd,
+        properties: HashMap<String, OwnedValue>,
+    },
+    Release,
+    RequestDisconnection {
+        device: OwnedObjectPath,
+    },
+}
+
+pub struct Profile {
+    pub(self) message_sender: mpsc::Sender<Message>,
+}
+
+#[zbus::interface(name = "org.bluez.Profile1")]
+impl Profile {
+    /// This method gets called when the service daemon
+    /// unregisters the profile. A profile can use it to do
+    /// cleanup tasks. There is no need to unregister the
+    /// profile, because when this method gets called it has
+    /// already been unregistered.
+    async fn release(&mut self) -> zbus::fdo::Result<()> {
+        tracing::debug!("release");
+
+        _ = self.message_sender.send(Message::Release).await;
+
+        Ok(())
+    }
+
+    /// This method gets called when a new service level
+    /// connection has been made and authorized.
+    ///
+    /// Common fd_properties:
+    ///
+    /// uint16 Version		Profile version (optional)
+    /// uint16 Features		Profile features (optional)
+    async fn new_connection(
+        &mut self,
+        device: OwnedObjectPath,
+        fd: zbus::zvariant::OwnedFd,
+        fd_properties: HashMap<String, OwnedValue>,
+    ) -> zbus::fdo::Result<()> {
+        tracing::debug!(?device, ?fd_properties, "new_connection");
+
+        _ = self
+            .message_sender
+            .send(Message::NewConnection {
+                device,
+                fd: OwnedFd::from(fd),
+                properties: fd_properties,
+            })
+            .await;
+
+        Ok(())
+    }
+
+    /// This method gets called when a profile gets
+    /// disconnected.
+    ///
+    /// The file descriptor is no longer owned by the service
+    /// daemon and the profile implementation needs to take
+    /// care of cleaning up all connections.
+    ///
+    /// If multiple file descriptors are indicated via
+    /// NewConnection, it is expected that all of them
+    /// are disconnected before returning from this
+    /// method call.
+    async fn request_disconnection(&mut self, device: OwnedObjectPath) -> zbus::fdo::Result<()> {
+        tracing::debug!(?device, "request_disconnection");
+
+        _ = self
+            .message_sender
+            .send(Message::RequestDisconnection { device })
+            .await;
+
+        Ok(())
+    }
+}
@@ -37,3 +37,21 @@ trait ProfileManager1 {
 	/// UnregisterProfile method
 	fn unregister_profile(&self, profile: &zbus::zvariant::ObjectPath<'_>) -> zbus::Result<()>;
 }
+
+impl ProfileManager1Proxy<'_> {
+	/// Registers the profile object at `profile` for `uuid` with typed options.
+	pub async fn register(
+		&self,
+		profile: &zbus::zvariant::ObjectPath<'_>,
+		uuid: &str,
+		options: &crate::profile1::Options,
+	) -> zbus::Result<()> {
+		let dict = options.to_dict();
+		self.register_profile(
+			profile,
+			uuid,
+			dict.iter().map(|(key, value)| (key.as_str(), value)).collect(),
+		)
+		.await
+	}
+}
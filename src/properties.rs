@@ -0,0 +1,86 @@
+//! Snapshots of all properties of an object, read with a single `GetAll` call.
+
+use std::collections::HashMap;
+
+use zbus::zvariant::{OwnedObjectPath, OwnedValue};
+
+use crate::address::AddressType;
+use crate::appearance::Appearance;
+use crate::class::DeviceClass;
+use crate::{changes, Uuid};
+
+/// The `org.bluez.Device1` properties of a device.
+///
+/// Properties that BlueZ only reports for some devices are `None` when absent.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeviceProperties {
+	pub adapter: Option<OwnedObjectPath>,
+	pub address: String,
+	pub address_type: Option<AddressType>,
+	pub alias: String,
+	pub appearance: Option<Appearance>,
+	pub blocked: bool,
+	pub class: Option<DeviceClass>,
+	pub connected: bool,
+	pub icon: Option<String>,
+	pub legacy_pairing: bool,
+	pub name: Option<String>,
+	pub paired: bool,
+	pub rssi: Option<i16>,
+	pub services_resolved: bool,
+	pub trusted: bool,
+	pub tx_power: Option<i16>,
+	/// Service UUIDs, skipping any that fail to parse.
+	pub uuids: Vec<Uuid>,
+}
+
+impl DeviceProperties {
+	/// Decodes the dictionary returned by `GetAll`.
+	pub fn from_dict(properties: &HashMap<String, OwnedValue>) -> Self {
+		Self {
+			adapter: get(properties, "Adapter"),
+			address: get(properties, "Address").unwrap_or_default(),
+			address_type: get::<String>(properties, "AddressType")
+				.and_then(|address_type| AddressType::try_from(address_type.as_str()).ok()),
+			alias: get(properties, "Alias").unwrap_or_default(),
+			appearance: get::<u16>(properties, "Appearance").map(Appearance::from),
+			blocked: get(properties, "Blocked").unwrap_or_default(),
+			class: get::<u32>(properties, "Class").map(DeviceClass::from),
+			connected: get(properties, "Connected").unwrap_or_default(),
+			icon: get(properties, "Icon"),
+			legacy_pairing: get(properties, "LegacyPairing").unwrap_or_default(),
+			name: get(properties, "Name"),
+			paired: get(properties, "Paired").unwrap_or_default(),
+			rssi: get(properties, "RSSI"),
+			services_resolved: get(properties, "ServicesResolved").unwrap_or_default(),
+			trusted: get(properties, "Trusted").unwrap_or_default(),
+			tx_power: get(properties, "TxPower"),
+			uuids: get::<Vec<String>>(properties, "UUIDs")
+				.unwrap_or_default()
+				.iter()
+				.filter_map(|uuid| uuid.parse().ok())
+				.collect(),
+		}
+	}
+}
+
+/// Reads every property of `interface` on the object at `path` in one call.
+pub(crate) async fn get_all(
+	connection: &zbus::Connection,
+	path: &zbus::zvariant::ObjectPath<'_>,
+	interface: &str,
+) -> zbus::Result<HashMap<String, OwnedValue>> {
+	let properties = zbus::fdo::PropertiesProxy::builder(connection)
+		.destination("org.bluez")?
+		.path(path)?
+		.build()
+		.await?;
+	properties.inner().call("GetAll", &(interface,)).await
+}
+
+fn get<T>(properties: &HashMap<String, OwnedValue>, name: &str) -> Option<T>
+where
+	T: TryFrom<OwnedValue>,
+{
+	properties.get(name).and_then(|value| changes::decode(value))
+}
@@ -0,0 +1,81 @@
+//! Tracking of the objects registered with BlueZ, to unregister them all on shutdown.
+
+use crate::{agent1, gatt_server, le_advertising_manager1};
+
+/// Something registered with BlueZ through this crate.
+#[derive(Debug)]
+pub enum Registered {
+	Agent(agent1::Registration),
+	Application(gatt_server::Application),
+	Advertisements(le_advertising_manager1::AdvertisementSet),
+}
+
+impl From<agent1::Registration> for Registered {
+	fn from(registration: agent1::Registration) -> Self {
+		Self::Agent(registration)
+	}
+}
+
+impl From<gatt_server::Application> for Registered {
+	fn from(application: gatt_server::Application) -> Self {
+		Self::Application(application)
+	}
+}
+
+impl From<le_advertising_manager1::AdvertisementSet> for Registered {
+	fn from(advertisements: le_advertising_manager1::AdvertisementSet) -> Self {
+		Self::Advertisements(advertisements)
+	}
+}
+
+/// Registrations that are undone together by [`Registry::shutdown`], so that
+/// no stale agent, application or advertisement outlives the process.
+#[derive(Debug, Default)]
+pub struct Registry {
+	registered: Vec<Registered>,
+}
+
+impl Registry {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Takes ownership of `registration` until shutdown.
+	pub fn add(&mut self, registration: impl Into<Registered>) {
+		self.registered.push(registration.into());
+	}
+
+	/// The tracked registrations, in the order they were added.
+	pub fn iter(&self) -> impl Iterator<Item = &Registered> {
+		self.registered.iter()
+	}
+
+	pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Registered> {
+		self.registered.iter_mut()
+	}
+
+	/// Unregisters everything, most recently added first.
+	///
+	/// Every registration is attempted even if an earlier one fails, in which
+	/// case the first error is returned.
+	pub async fn shutdown(mut self) -> zbus::Result<()> {
+		let mut result = Ok(());
+
+		while let Some(registered) = self.registered.pop() {
+			let unregistered = match registered {
+				Registered::Agent(registration) => registration.unregister().await,
+				Registered::Application(application) => application.unregister().await,
+				Registered::Advertisements(mut advertisements) => advertisements.clear().await,
+			};
+
+			if let Err(why) = unregistered {
+				tracing::warn!(?why, "failed to unregister during shutdown");
+				if result.is_ok() {
+					result = Err(why);
+				}
+			}
+		}
+
+		result
+	}
+}
@@ -0,0 +1,149 @@
+//! Bluetooth UUIDs.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// The Bluetooth Base UUID, `00000000-0000-1000-8000-00805f9b34fb`, which
+/// 16-bit and 32-bit UUIDs are expanded against.
+const BASE: u128 = 0x0000_0000_0000_1000_8000_0080_5f9b_34fb;
+
+/// A 128-bit Bluetooth UUID.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Uuid(pub u128);
+
+impl Uuid {
+	/// Expands a 16-bit UUID, such as `0x180D` for the Heart Rate service.
+	pub const fn from_u16(uuid: u16) -> Self {
+		Self::from_u32(uuid as u32)
+	}
+
+	/// Expands a 32-bit UUID.
+	pub const fn from_u32(uuid: u32) -> Self {
+		Self(BASE | (uuid as u128) << 96)
+	}
+
+	pub const fn as_u128(self) -> u128 {
+		self.0
+	}
+}
+
+impl FromStr for Uuid {
+	type Err = InvalidUuid;
+
+	/// Parses either the dashed `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx` form, or a
+	/// 16-bit or 32-bit UUID written as 4 or 8 hex digits with an optional `0x` prefix.
+	fn from_str(uuid: &str) -> Result<Self, Self::Err> {
+		let invalid = || InvalidUuid(uuid.to_owned());
+
+		let short = uuid
+			.strip_prefix("0x")
+			.or_else(|| uuid.strip_prefix("0X"))
+			.unwrap_or(uuid);
+		if short.chars().all(|c| c.is_ascii_hexdigit()) {
+			return match short.len() {
+				4 => u16::from_str_radix(short, 16)
+					.map(Self::from_u16)
+					.map_err(|_| invalid()),
+				8 => u32::from_str_radix(short, 16)
+					.map(Self::from_u32)
+					.map_err(|_| invalid()),
+				_ => Err(invalid()),
+			};
+		}
+
+		let bytes = uuid.as_bytes();
+		if bytes.len() != 36 || [8, 13, 18, 23].iter().any(|&index| bytes[index] != b'-') {
+			return Err(invalid());
+		}
+
+		let hex: String = uuid.chars().filter(|c| *c != '-').collect();
+		if hex.len() != 32 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+			return Err(invalid());
+		}
+
+		u128::from_str_radix(&hex, 16).map(Self).map_err(|_| invalid())
+	}
+}
+
+impl fmt::Display for Uuid {
+	/// Formats the UUID in the canonical lowercase dashed form BlueZ expects.
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let uuid = self.0;
+		write!(
+			f,
+			"{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+			uuid >> 96,
+			(uuid >> 80) & 0xffff,
+			(uuid >> 64) & 0xffff,
+			(uuid >> 48) & 0xffff,
+			uuid & 0xffff_ffff_ffff,
+		)
+	}
+}
+
+impl From<Uuid> for String {
+	fn from(uuid: Uuid) -> String {
+		uuid.to_string()
+	}
+}
+
+/// Error returned when parsing a string that is not a UUID.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidUuid(pub String);
+
+impl fmt::Display for InvalidUuid {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "invalid UUID: {}", self.0)
+	}
+}
+
+impl std::error::Error for InvalidUuid {}
+
+/// Well-known Bluetooth profiles, identified by the service class UUID the
+/// remote device implements.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Profile {
+	/// Serial Port Profile.
+	SerialPort,
+	/// Headset Profile, headset role.
+	Hsp,
+	/// Headset Profile, audio gateway role.
+	HspAudioGateway,
+	/// Advanced Audio Distribution Profile, audio source role, such as a phone.
+	A2dpSource,
+	/// Advanced Audio Distribution Profile, audio sink role, such as headphones.
+	A2dpSink,
+	/// A/V Remote Control Profile, target role.
+	AvrcpTarget,
+	/// A/V Remote Control Profile, controller role.
+	AvrcpController,
+	/// Personal Area Networking, user role.
+	Pan,
+	/// Personal Area Networking, network access point role.
+	Nap,
+	/// Hands-Free Profile, hands-free unit role.
+	Hfp,
+	/// Hands-Free Profile, audio gateway role.
+	HfpAudioGateway,
+	/// Human Interface Device Profile.
+	Hid,
+}
+
+impl Profile {
+	pub const fn uuid(self) -> Uuid {
+		Uuid::from_u16(match self {
+			Profile::SerialPort => 0x1101,
+			Profile::Hsp => 0x1108,
+			Profile::HspAudioGateway => 0x1112,
+			Profile::A2dpSource => 0x110A,
+			Profile::A2dpSink => 0x110B,
+			Profile::AvrcpTarget => 0x110C,
+			Profile::AvrcpController => 0x110E,
+			Profile::Pan => 0x1115,
+			Profile::Nap => 0x1116,
+			Profile::Hfp => 0x111E,
+			Profile::HfpAudioGateway => 0x111F,
+			Profile::Hid => 0x1124,
+		})
+	}
+}
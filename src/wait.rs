@@ -0,0 +1,36 @@
+//! Waiting for a condition to hold, with a timeout.
+
+use std::time::Duration;
+
+use futures_util::{Stream, StreamExt};
+
+use crate::BluezError;
+
+/// Waits for the first item of `stream` that satisfies `predicate`.
+///
+/// Returns [`BluezError::Timeout`] if no such item arrives within `timeout`, and
+/// [`BluezError::Failed`] if the stream ends first.
+pub async fn wait_for<S, T>(
+	stream: S,
+	mut predicate: impl FnMut(&T) -> bool,
+	timeout: Duration,
+) -> Result<T, BluezError>
+where
+	S: Stream<Item = T>,
+{
+	let mut stream = std::pin::pin!(stream);
+	let found = async {
+		while let Some(item) = stream.next().await {
+			if predicate(&item) {
+				return Some(item);
+			}
+		}
+		None
+	};
+
+	match tokio::time::timeout(timeout, found).await {
+		Ok(Some(item)) => Ok(item),
+		Ok(None) => Err(BluezError::Failed("stream ended before the condition held".to_owned())),
+		Err(_) => Err(BluezError::Timeout),
+	}
+}
@@ -0,0 +1,56 @@
+//! Drives the regular wrappers against the in-memory BlueZ of the `mock` feature.
+
+use std::time::Duration;
+
+use futures_util::StreamExt;
+
+use bluez_zbus::mock::MockBluez;
+use bluez_zbus::BluezError;
+
+const ADDRESS: &str = "AA:BB:CC:DD:EE:FF";
+
+async fn device_without_name() -> anyhow::Result<(MockBluez, bluez_zbus::mock::MockDevice)> {
+	let bluez = MockBluez::new().await?;
+	let adapter = bluez.add_adapter("hci0", "00:11:22:33:44:55").await?;
+	let device = adapter.add_device(ADDRESS, None).await?;
+	Ok((bluez, device))
+}
+
+#[tokio::test]
+async fn reads_all_properties_of_a_device_without_name() -> anyhow::Result<()> {
+	let (bluez, mock) = device_without_name().await?;
+	let device = bluez_zbus::get_device(bluez.connection(), mock.path().into()).await?;
+
+	let properties = device.properties().await?;
+	assert_eq!(properties.address, ADDRESS);
+	assert_eq!(properties.alias, "AA-BB-CC-DD-EE-FF");
+	assert!(!properties.connected);
+	assert_eq!(device.display_name().await?, "AA-BB-CC-DD-EE-FF");
+	Ok(())
+}
+
+#[tokio::test]
+async fn connects_once() -> anyhow::Result<()> {
+	let (bluez, mock) = device_without_name().await?;
+	let device = bluez_zbus::get_device(bluez.connection(), mock.path().into()).await?;
+
+	device.connect().await?;
+	device.wait_connected(Duration::from_secs(1)).await?;
+	assert!(device.services_resolved().await?);
+	assert!(matches!(device.connect().await, Err(BluezError::AlreadyConnected)));
+	Ok(())
+}
+
+#[tokio::test]
+async fn streams_rssi_updates() -> anyhow::Result<()> {
+	let (bluez, mock) = device_without_name().await?;
+	let device = bluez_zbus::get_device(bluez.connection(), mock.path().into()).await?;
+
+	let mut rssi = Box::pin(device.rssi_stream().await);
+	assert_eq!(rssi.next().await, Some(-60));
+
+	mock.set_rssi(-42).await?;
+	assert_eq!(rssi.next().await, Some(-42));
+	assert_eq!(device.rssi().await?, Some(-42));
+	Ok(())
+}